@@ -31,6 +31,9 @@ fn main() {
     }
 
     setup_rerun_triggers(&config);
+    emit_vendored_version(&config);
+    generate_stream_accessors(&config);
+    generate_language_tables(&config);
 
     // Ensure required tools are present for this target before attempting build
     preflight_check_tools(&config.target);
@@ -129,6 +132,288 @@ fn setup_rerun_triggers(config: &BuildConfig) {
     println!("cargo:rerun-if-env-changed=TARGET");
 }
 
+fn emit_vendored_version(config: &BuildConfig) {
+    // AC_INIT([libmediainfo], [25.07]) carries the version of the vendored sources
+    let configure_ac = config
+        .mediainfo_src
+        .join("MediaInfoLib/Project/GNU/Library/configure.ac");
+    let contents =
+        fs::read_to_string(&configure_ac).expect("Failed to read MediaInfoLib configure.ac");
+
+    let version = contents
+        .lines()
+        .find(|line| line.starts_with("AC_INIT("))
+        .and_then(|line| line.split('[').nth(2))
+        .and_then(|rest| rest.split(']').next())
+        .expect("Could not find the MediaInfoLib version in configure.ac");
+
+    println!("cargo:rustc-env=MEDIAINFO_VENDORED_VERSION={version}");
+}
+
+// Stream kinds in MediaInfoLib/Source/Resource/Text/Stream, with the Rust
+// types their accessors are implemented for.
+const STREAM_TABLES: &[(&str, &str, &str)] = &[
+    ("General", "GeneralStream", "GeneralSnapshot"),
+    ("Video", "VideoStream", "VideoSnapshot"),
+    ("Audio", "AudioStream", "AudioSnapshot"),
+    ("Text", "TextStream", "TextSnapshot"),
+    ("Other", "OtherStream", "OtherSnapshot"),
+    ("Image", "ImageStream", "ImageSnapshot"),
+    ("Menu", "MenuStream", "MenuSnapshot"),
+];
+
+fn generate_stream_accessors(config: &BuildConfig) {
+    let tables = config
+        .mediainfo_src
+        .join("MediaInfoLib/Source/Resource/Text/Stream");
+    let mut out = String::from(
+        "// @generated by build.rs from MediaInfoLib/Source/Resource/Text/Stream/*.csv\n",
+    );
+    // The same accessors as data, for lookups by name:
+    // (stream kind, accessor, field, value type)
+    let mut table = String::from(
+        "// @generated by build.rs from MediaInfoLib/Source/Resource/Text/Stream/*.csv\n\n\
+         static STREAM_FIELDS: &[(&str, &str, &str, &str)] = &[\n",
+    );
+
+    for (kind, stream, snapshot) in STREAM_TABLES {
+        let csv = tables.join(format!("{kind}.csv"));
+        println!("cargo:rerun-if-changed={}", csv.display());
+        let contents = fs::read_to_string(&csv)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {e}", csv.display()));
+
+        out.push_str(&format!("\nstream_accessors!({stream}, {snapshot} => {{\n"));
+        // Accessor name -> the field it reads.
+        let mut seen = std::collections::HashMap::new();
+
+        for line in contents.lines() {
+            // Name;Text;Measure;Options;Name_Text;Measure_Text;Info;HowTo
+            let columns: Vec<&str> = line.trim_start_matches('\u{feff}').split(';').collect();
+            let name = columns[0].trim();
+            if name.is_empty() {
+                continue;
+            }
+
+            let method = RENAMED_FIELDS
+                .iter()
+                .find(|(field, _)| *field == name)
+                .map_or_else(|| accessor_name(name), |(_, method)| method.to_string());
+            if let Some(other) = seen.insert(method.clone(), name) {
+                panic!(
+                    "{kind}.csv: `{other}` and `{name}` both map to `{method}`, \
+                     add one of them to RENAMED_FIELDS"
+                );
+            }
+
+            let measure = columns.get(2).copied().unwrap_or_default();
+            let options = columns.get(3).copied().unwrap_or_default();
+            let info = columns.get(6).copied().unwrap_or_default().trim();
+
+            let item = list_item(name);
+            let macro_name = match item {
+                Some(_) => "mediainfo_list",
+                None => accessor_macro(name, measure, options),
+            };
+            out.push_str(&format!("    {macro_name}!(\n"));
+            if !info.is_empty() {
+                out.push_str(&format!("        #[doc = {info:?}]\n"));
+            }
+            if info.starts_with("Deprecated") {
+                out.push_str(&format!("        #[deprecated(note = {info:?})]\n"));
+            }
+            match item {
+                Some(item) => out.push_str(&format!(
+                    "        {method},\n        {name:?},\n        {item}\n    );\n"
+                )),
+                None => out.push_str(&format!("        {method},\n        {name:?}\n    );\n")),
+            }
+
+            let value_type = macro_name.trim_start_matches("mediainfo_");
+            table.push_str(&format!(
+                "    ({kind:?}, {method:?}, {name:?}, {value_type:?}),\n"
+            ));
+
+            // Fields read as one value that may also hold several layers get
+            // a second accessor returning each of them.
+            if let Some(item) = layer_item(name) {
+                let list_method = format!("{method}_list");
+                if let Some(other) = seen.insert(list_method.clone(), name) {
+                    panic!(
+                        "{kind}.csv: `{other}` and the layers of `{name}` both map to \
+                         `{list_method}`, add one of them to RENAMED_FIELDS"
+                    );
+                }
+                out.push_str(&format!(
+                    "    mediainfo_list!(\n        #[doc = \"Every layer of `{name}`.\"]\n        \
+                     {list_method},\n        {name:?},\n        {item}\n    );\n"
+                ));
+                table.push_str(&format!(
+                    "    ({kind:?}, {list_method:?}, {name:?}, \"list\"),\n"
+                ));
+            }
+        }
+
+        out.push_str("});\n");
+    }
+    table.push_str("];\n");
+
+    fs::write(config.out_dir.join("stream_accessors.rs"), out)
+        .expect("Failed to write generated stream accessors");
+    fs::write(config.out_dir.join("stream_fields.rs"), table)
+        .expect("Failed to write generated stream field table");
+}
+
+// ISO 639 code mappings and English language names, from the tables MediaInfo
+// translates `Language` with.
+fn generate_language_tables(config: &BuildConfig) {
+    let resources = config
+        .mediainfo_src
+        .join("MediaInfoLib/Source/Resource/Text");
+    let read = |path: &str| {
+        let path = resources.join(path);
+        println!("cargo:rerun-if-changed={}", path.display());
+        fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {e}", path.display()))
+    };
+
+    let mut out = String::from(
+        "// @generated by build.rs from MediaInfoLib/Source/Resource/Text\n\n\
+         // (ISO 639-2 code, ISO 639-1 code), bibliographic and terminology forms\n\
+         static ISO639_2_TO_1: &[(&str, &str)] = &[\n",
+    );
+    for line in read("DataBase/Iso639_1.csv").lines() {
+        if let Some((three, two)) = line.trim_start_matches('\u{feff}').split_once(';') {
+            out.push_str(&format!("    ({:?}, {:?}),\n", three.trim(), two.trim()));
+        }
+    }
+
+    out.push_str("];\n\n// (code, English name)\nstatic LANGUAGE_NAMES: &[(&str, &str)] = &[\n");
+    for line in read("Language/DefaultLanguage.csv").lines() {
+        let Some((key, name)) = line.split_once(';') else {
+            continue;
+        };
+        // Skips the "Language_Info" and "Language_More" labels.
+        if let Some(code) = key.strip_prefix("Language_")
+            && code.starts_with(|c: char| c.is_ascii_lowercase())
+        {
+            out.push_str(&format!("    ({code:?}, {:?}),\n", name.trim()));
+        }
+    }
+    out.push_str("];\n");
+
+    fs::write(config.out_dir.join("language_tables.rs"), out)
+        .expect("Failed to write generated language tables");
+}
+
+// `Format_Settings_RefFrames/String` -> `format_settings_ref_frames_string`,
+// `Channel(s)` -> `channels`, `Bits-(Pixel*Frame)` -> `bits_pixel_frame`.
+fn accessor_name(field: &str) -> String {
+    let field = field.replace("(s)", "s");
+    let chars: Vec<char> = field.chars().collect();
+    let mut name = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            let prev = if i > 0 { chars[i - 1] } else { '_' };
+            let next = chars.get(i + 1).copied().unwrap_or('_');
+            if i > 0
+                && (prev.is_ascii_lowercase()
+                    || prev.is_ascii_digit()
+                    || (prev.is_ascii_uppercase() && next.is_ascii_lowercase()))
+            {
+                name.push('_');
+            }
+            name.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() {
+            name.push(c);
+        } else if !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+
+    let name = name.trim_matches('_').replace("__", "_");
+    match name.as_str() {
+        "type" => "type_".to_string(),
+        _ => name,
+    }
+}
+
+// Fields generated under another name than `accessor_name` gives them.
+// `codec_info` has always read `CodecID/Info` (see streams.rs), not the
+// deprecated `Codec/Info`.
+const RENAMED_FIELDS: &[(&str, &str)] = &[("Codec/Info", "codec_info_legacy")];
+
+// Fields whose table entry gives the wrong type on some streams: the menu
+// and other tables declare `Duration_Start` / `Duration_End` as text, the
+// original frame rate fraction as decimals and the subtitle line counts as
+// text, while the values are durations and integers.
+const TYPE_OVERRIDES: &[(&str, &str)] = &[
+    ("Duration_Start", "mediainfo_duration"),
+    ("Duration_End", "mediainfo_duration"),
+    ("FrameRate_Original_Num", "mediainfo_i64"),
+    ("FrameRate_Original_Den", "mediainfo_i64"),
+    ("Lines_MaxCountPerEvent", "mediainfo_i64"),
+    ("Lines_MaxCharacterCount", "mediainfo_i64"),
+];
+
+// Picks the accessor type from the field's measure unit, falling back to
+// the value type in the options column (`T`ext, `I`nteger, `F`loat).
+fn accessor_macro(field: &str, measure: &str, options: &str) -> &'static str {
+    if let Some((_, macro_name)) = TYPE_OVERRIDES.iter().find(|(name, _)| *name == field) {
+        return macro_name;
+    }
+    let value_type = options.chars().nth(3).unwrap_or('T');
+
+    match measure.trim() {
+        // Delays and timestamps are signed offsets, not lengths.
+        "ms" if field.contains("Delay") || field.contains("TimeStamp") => "mediainfo_f64",
+        "ms" => "mediainfo_duration",
+        "fps" => "mediainfo_frame_rate",
+        "Hz" | "dB" => "mediainfo_f64",
+        "bps" | "byte" | "bit" | "pixel" | "channel" | "character" | "frame"
+        | "slice per frame"
+            if value_type != 'T' =>
+        {
+            "mediainfo_i64"
+        }
+        _ if field.ends_with("_Date") && value_type == 'T' => "mediainfo_date",
+        _ if field.ends_with("_Proportion") => "mediainfo_percentage",
+        _ if field.ends_with("Ratio") || field.contains("AspectRatio_") && !field.contains('/') => {
+            "mediainfo_ratio"
+        }
+        _ => match value_type {
+            'I' => "mediainfo_i64",
+            'F' => "mediainfo_f64",
+            _ => "mediainfo_attr",
+        },
+    }
+}
+
+// Fields holding a list, and the type of their entries. The `*_List`
+// summaries of the general stream have one entry per stream of that kind;
+// `CodecID_Compatible` is the list of MP4 brands.
+fn list_item(field: &str) -> Option<&'static str> {
+    if field.ends_with("_Language_List") {
+        Some("LanguageTag")
+    } else if field.ends_with("_Format_List") || field.ends_with("_Format_WithHint_List") {
+        Some("FormatName")
+    } else if field.ends_with("_List") || field == "CodecID_Compatible" {
+        Some("String")
+    } else {
+        None
+    }
+}
+
+// Single value fields where MediaInfo merges one value per layer, and the
+// type of those values.
+fn layer_item(field: &str) -> Option<&'static str> {
+    match field {
+        "Format_Profile" => Some("FormatProfile"),
+        "ChannelPositions" | "ChannelPositions_Original" => Some("ChannelLayout"),
+        _ => None,
+    }
+}
+
 fn artifacts_exist(paths: &BuildPaths) -> bool {
     paths.zenlib_artifact.exists() && paths.mediainfo_artifact.exists()
 }
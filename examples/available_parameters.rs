@@ -1,8 +1,35 @@
 extern crate mediainfo;
 
-use mediainfo::MediaInfo;
+use mediainfo::{MediaInfo, MediaInfoStream};
+use std::env;
 
 fn main() {
     let mut media_info = MediaInfo::new();
-    println!("{}", media_info.available_parameters().unwrap());
+    let catalog = media_info.field_catalog().unwrap();
+    // Optional prefix filter, e.g. `cargo run --example available_parameters HDR_`
+    let prefix = env::args().nth(1).unwrap_or_default();
+
+    let kinds = [
+        MediaInfoStream::General,
+        MediaInfoStream::Video,
+        MediaInfoStream::Audio,
+        MediaInfoStream::Text,
+        MediaInfoStream::Other,
+        MediaInfoStream::Image,
+        MediaInfoStream::Menu,
+    ];
+
+    for kind in kinds {
+        println!("{}", kind.name());
+        for field in catalog.with_prefix(kind, &prefix) {
+            if field.is_string_variant {
+                continue;
+            }
+            println!(
+                "  {:<40} {:<10} {}",
+                field.name, field.measure, field.description
+            );
+        }
+        println!();
+    }
 }
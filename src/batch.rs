@@ -0,0 +1,533 @@
+// Analysis of many files across a pool of worker threads.
+//
+// Each worker owns one `MediaInfo` handle. Paths are pulled lazily from the
+// caller's iterator and at most twice as many files as there are workers are
+// in flight or waiting to be emitted, so memory stays bounded however long
+// the list is, in ordered mode too.
+//
+// libmediainfo cannot be interrupted. With a timeout the analysis runs on a
+// helper thread; when it overruns, the file is reported as timed out, the
+// worker carries on with a fresh handle and the helper is left to finish on
+// its own. At most `max_timed_out` such helpers are left running: beyond
+// that, workers wait for one of them to finish before taking another file,
+// so a directory of files that hang the parser cannot pile up threads.
+
+use crate::ffi::{MediaInfo, MediaInfoInfo, MediaInfoStream};
+use crate::snapshot::MediaSnapshot;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchAnalyzer {
+    /// Defaults to the available parallelism.
+    pub workers: usize,
+    /// Emit records in input order rather than as they finish.
+    pub ordered: bool,
+    pub timeout: Option<Duration>,
+    /// Timed out analyses left running before workers stop taking files.
+    pub max_timed_out: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    Analyzed,
+    /// Missing, not permitted or an I/O error.
+    Unreadable,
+    /// Read, but not a format MediaInfo recognizes.
+    Unsupported,
+    TimedOut,
+    Failed,
+}
+
+/// Why `MediaInfoWrapper::open_file` could not open a file.
+#[derive(Debug)]
+pub enum OpenError {
+    /// Missing, not permitted, a directory or an I/O error.
+    Unreadable(io::Error),
+    /// Read, but not a format MediaInfo recognizes.
+    Unsupported,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BatchRecord {
+    /// Position in the input list.
+    pub index: usize,
+    pub path: PathBuf,
+    pub status: BatchStatus,
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+    pub media: Option<MediaSnapshot>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchSummary {
+    pub total: usize,
+    pub analyzed: usize,
+    pub unreadable: usize,
+    pub unsupported: usize,
+    pub timed_out: usize,
+    pub failed: usize,
+    pub elapsed_ms: u64,
+}
+
+impl Default for BatchAnalyzer {
+    fn default() -> Self {
+        let workers = thread::available_parallelism().map_or(1, |x| x.get());
+        BatchAnalyzer {
+            workers,
+            ordered: false,
+            timeout: None,
+            max_timed_out: workers,
+        }
+    }
+}
+
+impl BatchAnalyzer {
+    /// Analyzes every path, handing each record to `on_record` on the calling
+    /// thread as soon as it may be emitted.
+    pub fn run<I>(&self, paths: I, mut on_record: impl FnMut(BatchRecord)) -> BatchSummary
+    where
+        I: IntoIterator,
+        I::Item: Into<PathBuf>,
+        I::IntoIter: Send,
+    {
+        self.run_while(paths, |record| {
+            on_record(record);
+            true
+        })
+    }
+
+    // `run`, taking no more files once `on_record` returns false. The files
+    // already started are finished but not emitted.
+    fn run_while<I>(&self, paths: I, mut on_record: impl FnMut(BatchRecord) -> bool) -> BatchSummary
+    where
+        I: IntoIterator,
+        I::Item: Into<PathBuf>,
+        I::IntoIter: Send,
+    {
+        let started = Instant::now();
+        let workers = self.workers.max(1);
+        let permits = Permits::new(2 * workers);
+        // One per running helper thread: each worker's current one plus the
+        // timed out ones.
+        let helpers = Arc::new(Permits::new(workers + self.max_timed_out));
+        let stopped = AtomicBool::new(false);
+        let (job_tx, job_rx) = mpsc::sync_channel::<(usize, PathBuf)>(workers);
+        let (record_tx, record_rx) = mpsc::sync_channel::<BatchRecord>(workers);
+        let job_rx = Mutex::new(job_rx);
+        let mut summary = BatchSummary::default();
+
+        thread::scope(|scope| {
+            let permits = &permits;
+            let job_rx = &job_rx;
+            let stopped = &stopped;
+            let paths = paths.into_iter();
+
+            scope.spawn(move || {
+                for (index, path) in paths.enumerate() {
+                    permits.acquire();
+                    if stopped.load(Ordering::Relaxed) || job_tx.send((index, path.into())).is_err()
+                    {
+                        break;
+                    }
+                }
+            });
+
+            for _ in 0..workers {
+                let record_tx = record_tx.clone();
+                let timeout = self.timeout;
+                let helpers = &helpers;
+                scope.spawn(move || {
+                    let mut handle = None;
+                    loop {
+                        let job = job_rx.lock().unwrap().recv();
+                        let Ok((index, path)) = job else {
+                            break;
+                        };
+                        let record = analyze(&mut handle, index, path, timeout, helpers);
+                        if record_tx.send(record).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(record_tx);
+
+            let mut emit = |record: BatchRecord| {
+                if !stopped.load(Ordering::Relaxed) {
+                    summary.add(&record);
+                    if !on_record(record) {
+                        stopped.store(true, Ordering::Relaxed);
+                    }
+                }
+                permits.release();
+            };
+            let mut pending = BTreeMap::new();
+            let mut next = 0;
+            for record in record_rx {
+                if !self.ordered {
+                    emit(record);
+                    continue;
+                }
+                pending.insert(record.index, record);
+                while let Some(record) = pending.remove(&next) {
+                    emit(record);
+                    next += 1;
+                }
+            }
+        });
+
+        summary.elapsed_ms = started.elapsed().as_millis() as u64;
+        summary
+    }
+
+    /// Writes one JSON object per file and line, then the summary as a last
+    /// `{"summary": {...}}` line.
+    pub fn write_ndjson<I, W>(&self, paths: I, mut writer: W) -> io::Result<BatchSummary>
+    where
+        I: IntoIterator,
+        I::Item: Into<PathBuf>,
+        I::IntoIter: Send,
+        W: Write,
+    {
+        let mut result = Ok(());
+        let summary = self.run_while(paths, |record| {
+            result = write_line(&mut writer, &record);
+            result.is_ok()
+        });
+        result?;
+
+        write_line(&mut writer, &serde_json::json!({ "summary": summary }))?;
+        writer.flush()?;
+        Ok(summary)
+    }
+}
+
+impl BatchSummary {
+    fn add(&mut self, record: &BatchRecord) {
+        self.total += 1;
+        match record.status {
+            BatchStatus::Analyzed => self.analyzed += 1,
+            BatchStatus::Unreadable => self.unreadable += 1,
+            BatchStatus::Unsupported => self.unsupported += 1,
+            BatchStatus::TimedOut => self.timed_out += 1,
+            BatchStatus::Failed => self.failed += 1,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.analyzed == self.total
+    }
+}
+
+impl fmt::Display for OpenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpenError::Unreadable(e) => write!(f, "{e}"),
+            OpenError::Unsupported => f.write_str("unsupported format"),
+        }
+    }
+}
+
+impl fmt::Display for BatchSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} files in {:.1} s: {} analyzed, {} unreadable, {} unsupported, {} timed out, {} failed",
+            self.total,
+            self.elapsed_ms as f64 / 1000.0,
+            self.analyzed,
+            self.unreadable,
+            self.unsupported,
+            self.timed_out,
+            self.failed
+        )
+    }
+}
+
+fn write_line<W: Write, T: Serialize>(writer: &mut W, value: &T) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, value)?;
+    writer.write_all(b"\n")
+}
+
+// A counting semaphore bounding the files between the feeder and the
+// emitter, and the helper threads of timed analyses.
+struct Permits {
+    available: Mutex<usize>,
+    released: Condvar,
+}
+
+impl Permits {
+    fn new(count: usize) -> Permits {
+        Permits {
+            available: Mutex::new(count),
+            released: Condvar::new(),
+        }
+    }
+
+    fn acquire(&self) {
+        let mut available = self.available.lock().unwrap();
+        while *available == 0 {
+            available = self.released.wait(available).unwrap();
+        }
+        *available -= 1;
+    }
+
+    fn release(&self) {
+        *self.available.lock().unwrap() += 1;
+        self.released.notify_one();
+    }
+}
+
+pub(crate) type Outcome = Result<MediaSnapshot, (BatchStatus, String)>;
+
+fn analyze(
+    handle: &mut Option<MediaInfo>,
+    index: usize,
+    path: PathBuf,
+    timeout: Option<Duration>,
+    helpers: &Arc<Permits>,
+) -> BatchRecord {
+    let started = Instant::now();
+    let outcome = match timeout {
+        None => analyze_file(handle.get_or_insert_with(MediaInfo::new), &path),
+        Some(timeout) => {
+            let mut media_info = handle.take().unwrap_or_default();
+            let file = path.clone();
+            let (tx, rx) = mpsc::channel();
+            helpers.acquire();
+            let helpers = Arc::clone(helpers);
+            thread::spawn(move || {
+                let outcome = analyze_file(&mut media_info, &file);
+                helpers.release();
+                let _ = tx.send((media_info, outcome));
+            });
+            match rx.recv_timeout(timeout) {
+                Ok((media_info, outcome)) => {
+                    *handle = Some(media_info);
+                    outcome
+                }
+                Err(_) => Err((
+                    BatchStatus::TimedOut,
+                    format!("no result after {} ms", timeout.as_millis()),
+                )),
+            }
+        }
+    };
+
+    let (status, error, media) = match outcome {
+        Ok(media) => (BatchStatus::Analyzed, None, Some(media)),
+        Err((status, error)) => (status, Some(error), None),
+    };
+    BatchRecord {
+        index,
+        path,
+        status,
+        error,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        media,
+    }
+}
+
+pub(crate) fn analyze_file(media_info: &mut MediaInfo, path: &Path) -> Outcome {
+    open_file(media_info, path).map_err(|e| {
+        let status = match e {
+            OpenError::Unreadable(_) => BatchStatus::Unreadable,
+            OpenError::Unsupported => BatchStatus::Unsupported,
+        };
+        (status, e.to_string())
+    })?;
+
+    let outcome =
+        MediaSnapshot::collect(media_info).map_err(|e| (BatchStatus::Failed, format!("{e:?}")));
+    media_info.close();
+    outcome
+}
+
+// Opens `path` and leaves it open when MediaInfo recognized its format.
+pub(crate) fn open_file(media_info: &mut MediaInfo, path: &Path) -> Result<(), OpenError> {
+    // MediaInfo reports missing and unrecognized files alike, and reads a
+    // directory as the files in it.
+    if fs::metadata(path).map_err(OpenError::Unreadable)?.is_dir() {
+        let e = io::Error::new(io::ErrorKind::IsADirectory, "is a directory");
+        return Err(OpenError::Unreadable(e));
+    }
+    fs::File::open(path).map_err(OpenError::Unreadable)?;
+
+    let format = match media_info.open(path) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => media_info
+            .get(
+                MediaInfoStream::General,
+                0,
+                "Format",
+                MediaInfoInfo::Text,
+                MediaInfoInfo::Name,
+            )
+            .ok(),
+    };
+    if format.is_none_or(|x| x.is_empty()) {
+        media_info.close();
+        return Err(OpenError::Unsupported);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn missing(count: usize) -> Vec<PathBuf> {
+        (0..count)
+            .map(|i| PathBuf::from(format!("/nonexistent/batch/{i}.mkv")))
+            .collect()
+    }
+
+    #[test]
+    fn ordered_runs_keep_input_order() {
+        let analyzer = BatchAnalyzer {
+            workers: 4,
+            ordered: true,
+            timeout: None,
+            max_timed_out: 4,
+        };
+        let mut indexes = Vec::new();
+        let summary = analyzer.run(missing(50), |record| {
+            assert_eq!(record.status, BatchStatus::Unreadable);
+            assert!(record.error.is_some() && record.media.is_none());
+            indexes.push(record.index);
+        });
+
+        assert_eq!(indexes, (0..50).collect::<Vec<_>>());
+        assert_eq!(summary.total, 50);
+        assert_eq!(summary.unreadable, 50);
+        assert!(!summary.is_success());
+    }
+
+    #[test]
+    fn writes_one_line_per_file_and_a_summary() {
+        let analyzer = BatchAnalyzer {
+            workers: 2,
+            ordered: false,
+            timeout: Some(Duration::from_secs(5)),
+            max_timed_out: 2,
+        };
+        let mut output = Vec::new();
+        let summary = analyzer.write_ndjson(missing(3), &mut output).unwrap();
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 4);
+        let mut indexes: Vec<u64> = lines[..3]
+            .iter()
+            .map(|x| x["index"].as_u64().unwrap())
+            .collect();
+        indexes.sort();
+        assert_eq!(indexes, [0, 1, 2]);
+        assert_eq!(lines[0]["status"], "unreadable");
+        assert_eq!(lines[3]["summary"]["unreadable"], 3);
+        assert_eq!(summary.total, 3);
+        assert!(summary.to_string().contains(": 0 analyzed, 3 unreadable"));
+    }
+
+    #[test]
+    fn stops_taking_files_after_a_write_error() {
+        struct ClosedPipe;
+
+        impl Write for ClosedPipe {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::BrokenPipe.into())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let analyzer = BatchAnalyzer {
+            workers: 1,
+            ordered: true,
+            timeout: None,
+            max_timed_out: 1,
+        };
+        let fed = Mutex::new(0);
+        let paths = missing(1000)
+            .into_iter()
+            .inspect(|_| *fed.lock().unwrap() += 1);
+        let error = analyzer.write_ndjson(paths, ClosedPipe).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::BrokenPipe);
+        // Only the files already queued when the write failed are read.
+        assert!(*fed.lock().unwrap() < 10);
+    }
+
+    // A FIFO without a writer blocks the analysis in `open` until one
+    // connects, which stands in for a file that hangs the parser.
+    #[cfg(unix)]
+    #[test]
+    fn bounds_analyses_left_running_after_a_timeout() {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = std::env::temp_dir().join(format!("mediainfo-batch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let fifos: Vec<PathBuf> = ["a", "b"].iter().map(|x| dir.join(x)).collect();
+        for fifo in &fifos {
+            let path = CString::new(fifo.as_os_str().as_bytes()).unwrap();
+            assert_eq!(unsafe { libc::mkfifo(path.as_ptr(), 0o600) }, 0);
+        }
+
+        let analyzer = BatchAnalyzer {
+            workers: 1,
+            ordered: false,
+            timeout: Some(Duration::from_millis(20)),
+            max_timed_out: 1,
+        };
+        let mut paths = fifos.clone();
+        paths.extend(missing(1));
+        let (tx, rx) = mpsc::channel();
+        thread::scope(|scope| {
+            scope.spawn(|| analyzer.run(paths, |record| tx.send(record).unwrap()));
+
+            for _ in 0..2 {
+                let record = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+                assert_eq!(record.status, BatchStatus::TimedOut);
+            }
+            // Two helpers are stuck: the worker waits for one to finish.
+            assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+
+            // Connecting and closing a writer lets the helper read to the end.
+            fs::OpenOptions::new().write(true).open(&fifos[0]).unwrap();
+            let record = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+            assert_eq!(record.status, BatchStatus::Unreadable);
+            fs::OpenOptions::new().write(true).open(&fifos[1]).unwrap();
+        });
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn permits_bound_work_in_flight() {
+        let permits = Permits::new(2);
+        permits.acquire();
+        permits.acquire();
+        assert_eq!(*permits.available.lock().unwrap(), 0);
+        permits.release();
+        permits.acquire();
+        assert_eq!(*permits.available.lock().unwrap(), 0);
+    }
+}
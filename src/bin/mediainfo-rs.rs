@@ -0,0 +1,647 @@
+// Command-line front end over `MediaInfoWrapper`, so scripts do not need the
+// C++ `mediainfo` CLI.
+//
+// Inputs are files, directories (walked recursively, in name order) and `-`
+// for stdin. With `--batch`, files are analyzed in parallel by
+// `BatchAnalyzer` and written as NDJSON, and `-` reads a list of paths, one
+// per line, instead of media data. With `--watch`, the one directory given
+// is watched and every file landing in it is analyzed once complete, until
+// the process is stopped; it needs the `watch` feature. Exit codes:
+//
+// - 0: every input was analyzed
+// - 1: an input could not be read (missing, permissions, I/O error)
+// - 2: bad command line
+// - 3: an input was read but MediaInfo does not recognize its format
+// - 4: an analysis timed out or failed (batch mode)
+//
+// When inputs fail for several reasons, 1 wins over 4 and 4 over 3.
+
+extern crate mediainfo;
+
+use mediainfo::{BatchAnalyzer, MediaInfoStream, MediaInfoWrapper, MediaSnapshot, OpenError};
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+use mediainfo::{Completion, WatchFolder, WatchOutput};
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+use std::{env, fs, io};
+
+const EXIT_UNREADABLE: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_UNSUPPORTED: u8 = 3;
+const EXIT_FAILED: u8 = 4;
+
+const USAGE: &str = "\
+Usage: mediainfo-rs [OPTIONS] <FILE|DIR|->...
+
+Options:
+  --output <FORMAT>   text (default), json, xml, ebucore... any output
+                      format the linked libmediainfo lists
+  --fields <LIST>     comma-separated fields, each `Field` for every stream
+                      or `Kind:Field`, e.g. General:Duration,Video:Width
+                      (text and json output only)
+  --full              include every field, as `mediainfo --Full`
+  --batch             analyze in parallel, one JSON object per file and line,
+                      then a summary line; `-` reads paths from stdin
+  --jobs <N>          batch workers (default: one per CPU)
+  --ordered           batch records in input order
+  --timeout <SECS>    give up on a file after SECS seconds (batch mode)
+  --watch             analyze files landing in DIR, as NDJSON on stdout
+  --sidecars <DIR>    write `<file>.json` sidecars to DIR instead (watch mode)
+  --ledger <FILE>     record processed files there, so restarts skip them
+                      (default: DIR/.mediainfo-watch.ndjson)
+  --settle <SECS>     complete once unchanged for SECS seconds (default 5)
+  --growing           complete when the writer closes the file, or after
+                      --settle seconds (default 60) without change
+  -h, --help          print this help";
+
+#[derive(Debug, Default, PartialEq)]
+struct Args {
+    output: Option<String>,
+    fields: Vec<FieldSpec>,
+    full: bool,
+    batch: bool,
+    jobs: Option<usize>,
+    ordered: bool,
+    timeout: Option<Duration>,
+    watch: bool,
+    sidecars: Option<PathBuf>,
+    ledger: Option<PathBuf>,
+    settle: Option<Duration>,
+    growing: bool,
+    inputs: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct FieldSpec {
+    /// `None` selects the field in every stream kind.
+    kind: Option<MediaInfoStream>,
+    name: String,
+}
+
+enum Input {
+    File(PathBuf),
+    Stdin,
+}
+
+enum Failure {
+    Unreadable(String),
+    Unsupported,
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("mediainfo-rs: {message}\n\n{USAGE}");
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    if args.batch {
+        return run_batch(&args);
+    }
+    if args.watch {
+        return run_watch(&args);
+    }
+
+    let output = match output_format(&args) {
+        Ok(output) => output,
+        Err(message) => {
+            eprintln!("mediainfo-rs: {message}");
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    let mut inputs = Vec::new();
+    let mut status = 0;
+    for input in &args.inputs {
+        if input == "-" {
+            inputs.push(Input::Stdin);
+            continue;
+        }
+        let mut files = Vec::new();
+        for (path, e) in collect_files(Path::new(input), &mut files) {
+            eprintln!("mediainfo-rs: {}: {e}", path.display());
+            status = EXIT_UNREADABLE;
+        }
+        inputs.extend(files.into_iter().map(Input::File));
+    }
+
+    let mut results = Vec::new();
+    for input in &inputs {
+        let name = match input {
+            Input::File(path) => path.display().to_string(),
+            Input::Stdin => "-".to_string(),
+        };
+        match analyze(input, &args, output.as_deref()) {
+            Ok(result) => results.push(result),
+            Err(Failure::Unreadable(e)) => {
+                eprintln!("mediainfo-rs: {name}: {e}");
+                status = EXIT_UNREADABLE;
+            }
+            Err(Failure::Unsupported) => {
+                eprintln!("mediainfo-rs: {name}: unsupported format");
+                if status == 0 {
+                    status = EXIT_UNSUPPORTED;
+                }
+            }
+        }
+    }
+
+    let json = output.as_deref() == Some("JSON");
+    if json && results.len() > 1 {
+        println!("[{}]", results.join(","));
+    } else {
+        for result in &results {
+            println!("{}", result.trim_end());
+        }
+    }
+    ExitCode::from(status)
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut parsed = Args::default();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = |flag: &str| match inline {
+            Some(value) => Ok(value.to_string()),
+            None => args.next().ok_or(format!("{flag} needs a value")),
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--output" => parsed.output = Some(value("--output")?),
+            "--fields" => {
+                for spec in value("--fields")?.split(',').filter(|x| !x.is_empty()) {
+                    parsed.fields.push(parse_field(spec)?);
+                }
+            }
+            "--full" => parsed.full = true,
+            "--batch" => parsed.batch = true,
+            "--jobs" => {
+                let jobs = value("--jobs")?;
+                match jobs.parse::<usize>() {
+                    Ok(n) if n > 0 => parsed.jobs = Some(n),
+                    _ => return Err(format!("invalid --jobs {jobs}")),
+                }
+            }
+            "--ordered" => parsed.ordered = true,
+            "--timeout" => parsed.timeout = Some(parse_secs("--timeout", &value("--timeout")?)?),
+            "--watch" => parsed.watch = true,
+            "--sidecars" => parsed.sidecars = Some(PathBuf::from(value("--sidecars")?)),
+            "--ledger" => parsed.ledger = Some(PathBuf::from(value("--ledger")?)),
+            "--settle" => parsed.settle = Some(parse_secs("--settle", &value("--settle")?)?),
+            "--growing" => parsed.growing = true,
+            "--" => parsed.inputs.extend(args.by_ref()),
+            _ if flag.starts_with("--") => return Err(format!("unknown option {flag}")),
+            _ => parsed.inputs.push(arg),
+        }
+    }
+
+    if parsed.inputs.is_empty() {
+        return Err("no input given".to_string());
+    }
+    if parsed.batch && (parsed.output.is_some() || !parsed.fields.is_empty() || parsed.full) {
+        return Err("--batch always writes NDJSON snapshots".to_string());
+    }
+    if !parsed.batch && (parsed.jobs.is_some() || parsed.ordered || parsed.timeout.is_some()) {
+        return Err("--jobs, --ordered and --timeout need --batch".to_string());
+    }
+    let watch_options = parsed.sidecars.is_some()
+        || parsed.ledger.is_some()
+        || parsed.settle.is_some()
+        || parsed.growing;
+    if parsed.watch {
+        if parsed.batch || parsed.output.is_some() || !parsed.fields.is_empty() || parsed.full {
+            return Err("--watch always writes NDJSON snapshots".to_string());
+        }
+        if parsed.inputs.len() != 1 || parsed.inputs[0] == "-" {
+            return Err("--watch takes one directory".to_string());
+        }
+    } else if watch_options {
+        return Err("--sidecars, --ledger, --settle and --growing need --watch".to_string());
+    }
+    Ok(Some(parsed))
+}
+
+fn parse_secs(flag: &str, value: &str) -> Result<Duration, String> {
+    match value.parse::<f64>() {
+        Ok(secs) if secs > 0.0 && secs.is_finite() => Ok(Duration::from_secs_f64(secs)),
+        _ => Err(format!("invalid {flag} {value}")),
+    }
+}
+
+fn parse_field(spec: &str) -> Result<FieldSpec, String> {
+    let Some((kind, name)) = spec.split_once(':') else {
+        return Ok(FieldSpec {
+            kind: None,
+            name: spec.to_string(),
+        });
+    };
+    let kind = MediaInfoStream::variants()
+        .into_iter()
+        .chain([MediaInfoStream::General])
+        .filter(|x| *x != MediaInfoStream::Max)
+        .find(|x| x.name().eq_ignore_ascii_case(kind))
+        .ok_or(format!("unknown stream kind {kind} in --fields"))?;
+    Ok(FieldSpec {
+        kind: Some(kind),
+        name: name.to_string(),
+    })
+}
+
+// The value for the `Output` option, `None` for MediaInfo's default text.
+fn output_format(args: &Args) -> Result<Option<String>, String> {
+    let requested = match args.output.as_deref() {
+        None => return Ok(None),
+        Some(x) if x.eq_ignore_ascii_case("text") => return Ok(None),
+        Some(x) => x,
+    };
+    if !args.fields.is_empty() && !requested.eq_ignore_ascii_case("json") {
+        return Err("--fields only supports text and json output".to_string());
+    }
+
+    let mut media_info = MediaInfoWrapper::new();
+    match media_info.output_format(requested) {
+        Ok(Some(format)) => Ok(Some(format.name)),
+        Ok(None) => {
+            let formats = media_info.output_formats().unwrap_or_default();
+            let names: Vec<&str> = formats.iter().map(|x| x.name.as_str()).collect();
+            Err(format!(
+                "unknown output format {requested}, expected text or one of {}",
+                names.join(", ")
+            ))
+        }
+        Err(e) => Err(format!("cannot list the output formats: {e:?}")),
+    }
+}
+
+// Lists the files under `path` in name order. Entries that cannot be read
+// are returned as errors and the walk goes on; a directory reached again
+// through a symlink is skipped, which also ends symlink loops.
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> Vec<(PathBuf, io::Error)> {
+    let mut errors = Vec::new();
+    walk(path, files, &mut errors, &mut HashSet::new());
+    errors
+}
+
+fn walk(
+    path: &Path,
+    files: &mut Vec<PathBuf>,
+    errors: &mut Vec<(PathBuf, io::Error)>,
+    visited: &mut HashSet<PathBuf>,
+) {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) => return errors.push((path.to_path_buf(), e)),
+    };
+    if !metadata.is_dir() {
+        files.push(path.to_path_buf());
+        return;
+    }
+    match fs::canonicalize(path) {
+        Ok(canonical) => {
+            if !visited.insert(canonical) {
+                return;
+            }
+        }
+        Err(e) => return errors.push((path.to_path_buf(), e)),
+    }
+
+    let mut entries = Vec::new();
+    match fs::read_dir(path) {
+        Ok(listing) => {
+            for entry in listing {
+                match entry {
+                    Ok(entry) => entries.push(entry.path()),
+                    Err(e) => errors.push((path.to_path_buf(), e)),
+                }
+            }
+        }
+        Err(e) => return errors.push((path.to_path_buf(), e)),
+    }
+    entries.sort();
+    for entry in entries {
+        walk(&entry, files, errors, visited);
+    }
+}
+
+fn run_batch(args: &Args) -> ExitCode {
+    let mut analyzer = BatchAnalyzer {
+        ordered: args.ordered,
+        timeout: args.timeout,
+        ..BatchAnalyzer::default()
+    };
+    if let Some(jobs) = args.jobs {
+        analyzer.workers = jobs;
+    }
+
+    // Directories are listed up front, stdin is read as the batch goes.
+    let mut status = 0;
+    let mut sources = Vec::new();
+    for input in &args.inputs {
+        if input == "-" {
+            sources.push(None);
+            continue;
+        }
+        let mut files = Vec::new();
+        if Path::new(input).is_dir() {
+            for (path, e) in collect_files(Path::new(input), &mut files) {
+                eprintln!("mediainfo-rs: {}: {e}", path.display());
+                status = EXIT_UNREADABLE;
+            }
+        } else {
+            // Missing files are reported in their record.
+            files.push(PathBuf::from(input));
+        }
+        sources.push(Some(files));
+    }
+    let paths =
+        sources
+            .into_iter()
+            .flat_map(|source| -> Box<dyn Iterator<Item = PathBuf> + Send> {
+                match source {
+                    Some(files) => Box::new(files.into_iter()),
+                    None => Box::new(stdin_paths()),
+                }
+            });
+
+    let stdout = io::BufWriter::new(io::stdout().lock());
+    let summary = match analyzer.write_ndjson(paths, stdout) {
+        Ok(summary) => summary,
+        Err(e) => {
+            eprintln!("mediainfo-rs: {e}");
+            return ExitCode::from(EXIT_UNREADABLE);
+        }
+    };
+    eprintln!("mediainfo-rs: {summary}");
+
+    if status == 0 {
+        status = if summary.unreadable > 0 {
+            EXIT_UNREADABLE
+        } else if summary.timed_out + summary.failed > 0 {
+            EXIT_FAILED
+        } else if summary.unsupported > 0 {
+            EXIT_UNSUPPORTED
+        } else {
+            0
+        };
+    }
+    ExitCode::from(status)
+}
+
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+fn run_watch(args: &Args) -> ExitCode {
+    let mut folder = WatchFolder::new(Path::new(&args.inputs[0]));
+    if let Some(ledger) = &args.ledger {
+        folder.ledger = Some(ledger.clone());
+    }
+    folder.completion = match (args.growing, args.settle) {
+        (false, settle) => Completion::StableSize(settle.unwrap_or(Duration::from_secs(5))),
+        (true, settle) => Completion::Growing(settle.unwrap_or(Duration::from_secs(60))),
+    };
+    let output = match &args.sidecars {
+        Some(dir) => WatchOutput::Sidecars(dir.clone()),
+        None => WatchOutput::Stdout,
+    };
+
+    match folder.run(output) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("mediainfo-rs: {}: {e}", args.inputs[0]);
+            ExitCode::from(EXIT_UNREADABLE)
+        }
+    }
+}
+
+#[cfg(not(all(feature = "watch", not(target_arch = "wasm32"))))]
+fn run_watch(_: &Args) -> ExitCode {
+    eprintln!("mediainfo-rs: --watch needs a build with the `watch` feature");
+    ExitCode::from(EXIT_USAGE)
+}
+
+// Paths, one per line, read only as the batch needs them.
+fn stdin_paths() -> impl Iterator<Item = PathBuf> + Send {
+    std::iter::from_fn(|| {
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line),
+        }
+    })
+    .map(|line| PathBuf::from(line.trim_end_matches(['\n', '\r'])))
+    .filter(|path| !path.as_os_str().is_empty())
+}
+
+fn analyze(input: &Input, args: &Args, output: Option<&str>) -> Result<String, Failure> {
+    let mut media_info = MediaInfoWrapper::new();
+    match input {
+        Input::File(path) => media_info.open_file(path).map_err(|e| match e {
+            OpenError::Unreadable(e) => Failure::Unreadable(e.to_string()),
+            OpenError::Unsupported => Failure::Unsupported,
+        })?,
+        Input::Stdin => {
+            let mut data = Vec::new();
+            io::stdin()
+                .read_to_end(&mut data)
+                .map_err(|e| Failure::Unreadable(e.to_string()))?;
+            if data.is_empty() {
+                return Err(Failure::Unreadable("no data on stdin".to_string()));
+            }
+            media_info
+                .open_data(&data)
+                .map_err(|_| Failure::Unsupported)?;
+            if media_info.format().is_err() {
+                return Err(Failure::Unsupported);
+            }
+        }
+    }
+
+    if !args.fields.is_empty() {
+        let snapshot = media_info
+            .snapshot()
+            .map_err(|e| Failure::Unreadable(format!("{e:?}")))?;
+        return Ok(render_fields(
+            &snapshot,
+            &args.fields,
+            output == Some("JSON"),
+        ));
+    }
+
+    let mut options = vec![("Output", output.unwrap_or_default())];
+    if args.full {
+        options.push(("Complete", "1"));
+    }
+    media_info
+        .inform_with_options(&options)
+        .map_err(|e| Failure::Unreadable(format!("{e:?}")))
+}
+
+fn render_fields(snapshot: &MediaSnapshot, specs: &[FieldSpec], json: bool) -> String {
+    let streams = [(MediaInfoStream::General, vec![&snapshot.general.fields])]
+        .into_iter()
+        .chain([
+            (
+                MediaInfoStream::Video,
+                snapshot.video.iter().map(|x| &x.fields).collect(),
+            ),
+            (
+                MediaInfoStream::Audio,
+                snapshot.audio.iter().map(|x| &x.fields).collect(),
+            ),
+            (
+                MediaInfoStream::Text,
+                snapshot.text.iter().map(|x| &x.fields).collect(),
+            ),
+            (
+                MediaInfoStream::Other,
+                snapshot.other.iter().map(|x| &x.fields).collect(),
+            ),
+            (
+                MediaInfoStream::Image,
+                snapshot.image.iter().map(|x| &x.fields).collect(),
+            ),
+            (
+                MediaInfoStream::Menu,
+                snapshot.menu.iter().map(|x| &x.fields).collect(),
+            ),
+        ]);
+
+    let mut selected: Vec<(MediaInfoStream, usize, BTreeMap<&str, &str>)> = Vec::new();
+    for (kind, streams) in streams {
+        for (index, fields) in streams.into_iter().enumerate() {
+            let values: BTreeMap<&str, &str> = specs
+                .iter()
+                .filter(|spec| spec.kind.is_none_or(|x| x == kind))
+                .filter_map(|spec| {
+                    let value = fields.get(&spec.name).filter(|x| !x.is_empty())?;
+                    Some((spec.name.as_str(), value.as_str()))
+                })
+                .collect();
+            if !values.is_empty() {
+                selected.push((kind, index, values));
+            }
+        }
+    }
+
+    if json {
+        let streams: Vec<serde_json::Value> = selected
+            .iter()
+            .map(|(kind, index, values)| {
+                serde_json::json!({ "kind": kind.name(), "index": index, "fields": values })
+            })
+            .collect();
+        return serde_json::json!({
+            "file": snapshot.general.get("CompleteName").unwrap_or("-"),
+            "streams": streams,
+        })
+        .to_string();
+    }
+
+    let mut text = String::new();
+    for (kind, index, values) in selected {
+        text.push_str(&format!("{} #{index}\n", kind.name()));
+        for (name, value) in values {
+            text.push_str(&format!("{name:<40} : {value}\n"));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Result<Option<Args>, String> {
+        parse_args(line.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn parses_options_and_inputs() {
+        let parsed = args("--output=json --full --fields Duration,video:Width a.mkv - dir")
+            .unwrap()
+            .unwrap();
+        assert_eq!(parsed.output.as_deref(), Some("json"));
+        assert!(parsed.full);
+        assert_eq!(parsed.inputs, ["a.mkv", "-", "dir"]);
+        assert_eq!(
+            parsed.fields,
+            [
+                FieldSpec {
+                    kind: None,
+                    name: "Duration".to_string(),
+                },
+                FieldSpec {
+                    kind: Some(MediaInfoStream::Video),
+                    name: "Width".to_string(),
+                },
+            ]
+        );
+
+        assert_eq!(args("-- --full").unwrap().unwrap().inputs, ["--full"]);
+        assert!(args("--help a.mkv").unwrap().is_none());
+
+        let batch = args("--batch --jobs 8 --ordered --timeout 2.5 -")
+            .unwrap()
+            .unwrap();
+        assert_eq!(batch.jobs, Some(8));
+        assert!(batch.ordered);
+        assert_eq!(batch.timeout, Some(Duration::from_millis(2500)));
+
+        let watch = args("--watch --growing --settle 30 --sidecars out --ledger seen drop")
+            .unwrap()
+            .unwrap();
+        assert!(watch.watch && watch.growing);
+        assert_eq!(watch.settle, Some(Duration::from_secs(30)));
+        assert_eq!(watch.sidecars, Some(PathBuf::from("out")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn walks_past_unreadable_entries_and_symlink_loops() {
+        use std::os::unix::fs::symlink;
+
+        let dir = env::temp_dir().join(format!("mediainfo-rs-walk-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("b")).unwrap();
+        fs::write(dir.join("a.mkv"), b"").unwrap();
+        fs::write(dir.join("b/c.mkv"), b"").unwrap();
+        symlink(&dir, dir.join("b/loop")).unwrap();
+        symlink(dir.join("missing"), dir.join("broken")).unwrap();
+
+        let mut files = Vec::new();
+        let errors = collect_files(&dir, &mut files);
+
+        assert_eq!(files, [dir.join("a.mkv"), dir.join("b/c.mkv")]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, dir.join("broken"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_bad_command_lines() {
+        assert!(args("").is_err());
+        assert!(args("--output").is_err());
+        assert!(args("--frobnicate a.mkv").is_err());
+        assert!(args("--fields Sound:Format a.mkv").is_err());
+        assert!(args("--batch --output xml a.mkv").is_err());
+        assert!(args("--jobs 4 a.mkv").is_err());
+        assert!(args("--batch --jobs 0 a.mkv").is_err());
+        assert!(args("--batch --timeout -1 a.mkv").is_err());
+        assert!(args("--watch a b").is_err());
+        assert!(args("--watch --batch drop").is_err());
+        assert!(args("--ledger seen.ndjson drop").is_err());
+    }
+}
@@ -0,0 +1,121 @@
+// HTTP analysis service for programs that cannot link the crate. See
+// `AnalysisServer` for the endpoints. Built with `--features server`.
+
+extern crate mediainfo;
+
+use mediainfo::{AnalysisServer, ServerOptions};
+
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: mediainfo-server [OPTIONS]
+
+Options:
+  --listen <ADDR>         address to listen on (default 127.0.0.1:8080)
+  --max-body <BYTES>      largest accepted upload, with an optional K, M or G
+                          suffix (default 1G)
+  --max-concurrent <N>    analyses at once (default: one per CPU)
+  --allow <DIR>           directory whose files may be analyzed by path,
+                          may be repeated
+  -h, --help              print this help";
+
+fn main() -> ExitCode {
+    let (listen, options) = match parse_args(env::args().skip(1)) {
+        Ok(Some(parsed)) => parsed,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("mediainfo-server: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let server = match AnalysisServer::bind(&listen, options) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("mediainfo-server: {listen}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(addr) = server.local_addr() {
+        eprintln!("mediainfo-server: listening on http://{addr}");
+    }
+    server.run();
+    ExitCode::SUCCESS
+}
+
+fn parse_args(
+    mut args: impl Iterator<Item = String>,
+) -> Result<Option<(String, ServerOptions)>, String> {
+    let mut listen = "127.0.0.1:8080".to_string();
+    let mut options = ServerOptions::default();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = |flag: &str| match inline {
+            Some(value) => Ok(value.to_string()),
+            None => args.next().ok_or(format!("{flag} needs a value")),
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--listen" => listen = value("--listen")?,
+            "--max-body" => {
+                let size = value("--max-body")?;
+                options.max_body_size =
+                    parse_size(&size).ok_or(format!("invalid --max-body {size}"))?;
+            }
+            "--max-concurrent" => {
+                let count = value("--max-concurrent")?;
+                match count.parse::<usize>() {
+                    Ok(n) if n > 0 => options.max_concurrent = n,
+                    _ => return Err(format!("invalid --max-concurrent {count}")),
+                }
+            }
+            "--allow" => options.allowed_paths.push(PathBuf::from(value("--allow")?)),
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+    Ok(Some((listen, options)))
+}
+
+fn parse_size(input: &str) -> Option<u64> {
+    let (digits, shift) = match input.chars().last()? {
+        'k' | 'K' => (&input[..input.len() - 1], 10),
+        'm' | 'M' => (&input[..input.len() - 1], 20),
+        'g' | 'G' => (&input[..input.len() - 1], 30),
+        _ => (input, 0),
+    };
+    digits.parse::<u64>().ok()?.checked_mul(1 << shift)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_options() {
+        let args = "--listen 0.0.0.0:9000 --max-body=512M --max-concurrent 2 --allow /a --allow /b";
+        let (listen, options) = parse_args(args.split_whitespace().map(str::to_string))
+            .unwrap()
+            .unwrap();
+        assert_eq!(listen, "0.0.0.0:9000");
+        assert_eq!(options.max_body_size, 512 << 20);
+        assert_eq!(options.max_concurrent, 2);
+        assert_eq!(
+            options.allowed_paths,
+            [PathBuf::from("/a"), PathBuf::from("/b")]
+        );
+
+        assert_eq!(parse_size("1024"), Some(1024));
+        assert_eq!(parse_size("2k"), Some(2048));
+        assert_eq!(parse_size("G"), None);
+        assert!(parse_args(["--max-concurrent", "0"].map(str::to_string).into_iter()).is_err());
+    }
+}
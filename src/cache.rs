@@ -0,0 +1,337 @@
+// On-disk cache of snapshots, so re-scanning a library only analyzes the
+// files that changed.
+//
+// Entries are JSON files in one directory, one per path. An entry is used
+// only when the file still has the same size, modification time and, on
+// Unix, device and inode, and when it was made by the same libmediainfo
+// version with the same options; anything else is a miss and the entry is
+// replaced. With `hash_content` a hash of the whole file is compared too,
+// which catches rewrites that keep size and mtime but reads every file on
+// every lookup. The hash is FNV-1a, meant to detect changes, not tampering.
+//
+// Most MediaInfo options are process-wide. `options` are applied around
+// each analysis with `MediaInfo::with_options`, which holds a process-wide
+// lock and restores the previous values, so they do not leak into other
+// handles.
+
+use crate::convenience_api::MediaInfoWrapper;
+use crate::ffi::{MediaInfo, MediaInfoError, MediaInfoResult};
+use crate::snapshot::MediaSnapshot;
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::UNIX_EPOCH;
+
+pub struct AnalysisCache {
+    pub dir: PathBuf,
+    /// Also compare a hash of the content.
+    pub hash_content: bool,
+    /// MediaInfo options set before each analysis, e.g. ("ParseSpeed",
+    /// "1"). Entries made with other options are not used.
+    pub options: Vec<(String, String)>,
+    library_version: OnceLock<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FileIdentity {
+    /// Canonical path.
+    pub path: PathBuf,
+    pub size: u64,
+    /// Nanoseconds since the Unix epoch.
+    pub modified_ns: u64,
+    pub device: Option<u64>,
+    pub inode: Option<u64>,
+    pub content_hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    identity: FileIdentity,
+    library_version: String,
+    options: Vec<(String, String)>,
+    snapshot: MediaSnapshot,
+}
+
+impl AnalysisCache {
+    pub fn new(dir: &Path) -> AnalysisCache {
+        AnalysisCache {
+            dir: dir.to_path_buf(),
+            hash_content: false,
+            options: Vec::new(),
+            library_version: OnceLock::new(),
+        }
+    }
+
+    /// The snapshot of `path`, from the cache when it is still valid,
+    /// otherwise analyzed and stored.
+    pub fn snapshot(&self, path: &Path) -> MediaInfoResult<MediaSnapshot> {
+        let identity = self.identity(path)?;
+        if let Some(snapshot) = self.lookup(&identity) {
+            return Ok(snapshot);
+        }
+
+        let options: Vec<(&str, &str)> = self
+            .options
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        let mut media_info = MediaInfo::new();
+        let snapshot = media_info.with_options(&options, |media_info| {
+            if media_info.open(&identity.path)? == 0 {
+                return Err(MediaInfoError::NoDataOpen);
+            }
+            MediaSnapshot::collect(media_info)
+        })?;
+        self.store(identity, &snapshot)?;
+        Ok(snapshot)
+    }
+
+    /// The cached snapshot of `path` if it is still valid, without
+    /// analyzing anything.
+    pub fn get(&self, path: &Path) -> MediaInfoResult<Option<MediaSnapshot>> {
+        Ok(self.lookup(&self.identity(path)?))
+    }
+
+    /// Removes every entry.
+    pub fn clear(&self) -> MediaInfoResult<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(io_error(e)),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn identity(&self, path: &Path) -> MediaInfoResult<FileIdentity> {
+        let path = fs::canonicalize(path).map_err(io_error)?;
+        let metadata = fs::metadata(&path).map_err(io_error)?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|x| x.duration_since(UNIX_EPOCH).ok())
+            .unwrap_or_default();
+
+        #[cfg(unix)]
+        let (device, inode) = {
+            use std::os::unix::fs::MetadataExt;
+            (Some(metadata.dev()), Some(metadata.ino()))
+        };
+        #[cfg(not(unix))]
+        let (device, inode) = (None, None);
+
+        let content_hash = match self.hash_content {
+            true => Some(content_hash(&path).map_err(io_error)?),
+            false => None,
+        };
+        Ok(FileIdentity {
+            path,
+            size: metadata.len(),
+            modified_ns: modified.as_nanos() as u64,
+            device,
+            inode,
+            content_hash,
+        })
+    }
+
+    fn lookup(&self, identity: &FileIdentity) -> Option<MediaSnapshot> {
+        let data = fs::read(self.entry_path(identity)).ok()?;
+        // Unreadable entries, e.g. from an older crate, are misses.
+        let entry: Entry = serde_json::from_slice(&data).ok()?;
+        let valid = entry.identity == *identity
+            && entry.library_version == *self.library_version()
+            && entry.options == self.options;
+        valid.then_some(entry.snapshot)
+    }
+
+    fn store(&self, identity: FileIdentity, snapshot: &MediaSnapshot) -> MediaInfoResult<()> {
+        let target = self.entry_path(&identity);
+        let entry = Entry {
+            identity,
+            library_version: self.library_version().clone(),
+            options: self.options.clone(),
+            snapshot: snapshot.clone(),
+        };
+        let data = serde_json::to_vec(&entry).map_err(|e| MediaInfoError::Io(e.to_string()))?;
+
+        // Written whole then renamed, so concurrent scans never read half an
+        // entry. The name is unique per process and call.
+        static STORES: AtomicU64 = AtomicU64::new(0);
+        fs::create_dir_all(&self.dir).map_err(io_error)?;
+        let partial = target.with_extension(format!(
+            "{}-{}.partial",
+            std::process::id(),
+            STORES.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&partial, data).map_err(io_error)?;
+        fs::rename(&partial, &target).map_err(io_error)
+    }
+
+    fn entry_path(&self, identity: &FileIdentity) -> PathBuf {
+        let mut hasher = Fnv1a::new();
+        hasher.write(identity.path.as_os_str().as_encoded_bytes());
+        self.dir.join(format!("{:016x}.json", hasher.finish()))
+    }
+
+    fn library_version(&self) -> &String {
+        self.library_version.get_or_init(|| {
+            MediaInfoWrapper::new()
+                .version()
+                .map_or("unknown".to_string(), |x| x.to_string())
+        })
+    }
+}
+
+fn io_error(e: io::Error) -> MediaInfoError {
+    MediaInfoError::Io(e.to_string())
+}
+
+fn content_hash(path: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Fnv1a::new();
+    let mut chunk = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut chunk)?;
+        if read == 0 {
+            break;
+        }
+        hasher.write(&chunk[..read]);
+    }
+    Ok(format!("{:016x}", hasher.finish()))
+}
+
+// 64-bit FNV-1a. `DefaultHasher` is not guaranteed to give the same result
+// across Rust releases, which would orphan every entry on upgrade.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Fnv1a {
+        Fnv1a(0xcbf29ce484222325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::GeneralSnapshot;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("mediainfo-cache-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn snapshot(format: &str) -> MediaSnapshot {
+        MediaSnapshot {
+            general: GeneralSnapshot::from_pairs(0, &[("Format", format)]),
+            video: Vec::new(),
+            audio: Vec::new(),
+            text: Vec::new(),
+            other: Vec::new(),
+            image: Vec::new(),
+            menu: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn entries_are_invalidated_by_file_changes() {
+        let dir = temp_dir("files");
+        let file = dir.join("movie.mkv");
+        fs::write(&file, b"first").unwrap();
+        let cache = AnalysisCache::new(&dir.join("cache"));
+
+        assert_eq!(cache.get(&file).unwrap(), None);
+        cache
+            .store(cache.identity(&file).unwrap(), &snapshot("Matroska"))
+            .unwrap();
+        assert_eq!(cache.get(&file).unwrap(), Some(snapshot("Matroska")));
+        // The same file through another path is the same entry.
+        let relative = dir.join(".").join("movie.mkv");
+        assert_eq!(cache.get(&relative).unwrap(), Some(snapshot("Matroska")));
+
+        fs::write(&file, b"second, longer").unwrap();
+        assert_eq!(cache.get(&file).unwrap(), None);
+        assert!(matches!(
+            cache.get(&dir.join("missing.mkv")),
+            Err(MediaInfoError::Io(_))
+        ));
+
+        cache.clear().unwrap();
+        assert!(!cache.dir.exists());
+    }
+
+    #[test]
+    fn entries_are_invalidated_by_options_and_version() {
+        let dir = temp_dir("options");
+        let file = dir.join("clip.mp4");
+        fs::write(&file, b"content").unwrap();
+
+        let mut cache = AnalysisCache::new(&dir.join("cache"));
+        cache.hash_content = true;
+        cache.options = vec![("ParseSpeed".to_string(), "1".to_string())];
+        let identity = cache.identity(&file).unwrap();
+        let mut hasher = Fnv1a::new();
+        hasher.write(b"content");
+        let expected = format!("{:016x}", hasher.finish());
+        assert_eq!(identity.content_hash, Some(expected));
+        cache.store(identity, &snapshot("MPEG-4")).unwrap();
+        assert!(cache.get(&file).unwrap().is_some());
+
+        cache.options = vec![("ParseSpeed".to_string(), "0.5".to_string())];
+        assert!(cache.get(&file).unwrap().is_none());
+
+        cache.options = vec![("ParseSpeed".to_string(), "1".to_string())];
+        cache.library_version = OnceLock::from("0.0.1".to_string());
+        assert!(cache.get(&file).unwrap().is_none());
+    }
+
+    #[test]
+    fn concurrent_stores_use_their_own_partial_files() {
+        let dir = temp_dir("threads");
+        let file = dir.join("movie.mkv");
+        fs::write(&file, b"content").unwrap();
+        let cache = AnalysisCache::new(&dir.join("cache"));
+        let identity = cache.identity(&file).unwrap();
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..20 {
+                        cache
+                            .store(identity.clone(), &snapshot("Matroska"))
+                            .unwrap();
+                    }
+                });
+            }
+        });
+
+        assert_eq!(cache.get(&file).unwrap(), Some(snapshot("Matroska")));
+        assert_eq!(fs::read_dir(&cache.dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn fnv1a_matches_reference_values() {
+        let hash = |bytes: &[u8]| {
+            let mut hasher = Fnv1a::new();
+            hasher.write(bytes);
+            hasher.finish()
+        };
+        assert_eq!(hash(b""), 0xcbf29ce484222325);
+        assert_eq!(hash(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(hash(b"foobar"), 0x85944171f73967e8);
+    }
+}
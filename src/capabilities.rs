@@ -0,0 +1,292 @@
+// Typed wrappers around the `Info_*` options describing what the linked
+// libmediainfo supports.
+
+use crate::ffi::{MediaInfo, MediaInfoError, MediaInfoResult};
+use crate::field_catalog::split_row;
+
+use std::fmt;
+
+/// The libmediainfo version `mediainfo_src` was vendored at, read from its
+/// `configure.ac` by the build script.
+pub const VENDORED_VERSION: &str = env!("MEDIAINFO_VENDORED_VERSION");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LibraryVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl LibraryVersion {
+    /// Parses MediaInfo's `YY.MM[.patch]` versions, optionally prefixed the
+    /// way `Info_Version` reports them ("MediaInfoLib - v25.07").
+    pub fn parse(input: &str) -> Option<LibraryVersion> {
+        let input = input.trim();
+        let input = match input.rfind(" v") {
+            Some(pos) => &input[pos + 2..],
+            None => input.trim_start_matches('v'),
+        };
+        let input = input.split_whitespace().next()?;
+
+        let mut parts = input.split('.').map(|p| p.parse::<u32>());
+        let major = parts.next()?.ok()?;
+        let minor = match parts.next() {
+            Some(p) => p.ok()?,
+            None => 0,
+        };
+        let patch = match parts.next() {
+            Some(p) => p.ok()?,
+            None => 0,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(LibraryVersion {
+            major,
+            minor,
+            patch,
+        })
+    }
+
+    pub fn vendored() -> LibraryVersion {
+        LibraryVersion::parse(VENDORED_VERSION).expect("vendored version is valid")
+    }
+}
+
+impl fmt::Display for LibraryVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{:02}", self.major, self.minor)?;
+        if self.patch != 0 {
+            write!(f, ".{}", self.patch)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Codec {
+    pub id: String,
+    pub name: String,
+    /// How the ID is stored, e.g. "4CC" or "2CC".
+    pub kind_of_code: String,
+    /// "V", "A", "T"... following MediaInfo's stream kinds.
+    pub kind_of_stream: String,
+    pub family: String,
+    pub bit_rate_mode: String,
+    pub description: String,
+    pub url: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutputFormat {
+    /// The value to pass to the `Output` option.
+    pub name: String,
+    pub description: String,
+    pub mime_type: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PluginState {
+    Available,
+    Unavailable,
+    /// The library was compiled without support for the feature.
+    Disabled,
+}
+
+impl PluginState {
+    fn from_option(result: MediaInfoResult<String>) -> PluginState {
+        match result.as_deref().map(str::trim) {
+            Ok("1") => PluginState::Available,
+            Ok("0") => PluginState::Unavailable,
+            _ => PluginState::Disabled,
+        }
+    }
+}
+
+pub(crate) fn parse_codecs(info_codecs: &str) -> Vec<Codec> {
+    info_codecs
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let columns = split_row(line, ';');
+            let column = |i: usize| columns.get(i).cloned().unwrap_or_default();
+            Codec {
+                id: column(0),
+                name: column(1),
+                kind_of_code: column(2),
+                kind_of_stream: column(3),
+                family: column(4),
+                bit_rate_mode: column(5),
+                description: column(6),
+                url: column(7),
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn parse_output_formats(info_output_formats_csv: &str) -> Vec<OutputFormat> {
+    info_output_formats_csv
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let columns = split_row(line, ',');
+            let column = |i: usize| columns.get(i).cloned().unwrap_or_default();
+            OutputFormat {
+                name: column(0),
+                description: column(1),
+                mime_type: column(2),
+            }
+        })
+        .collect()
+}
+
+// Older libraries do not list their formats: `name` is then passed on for
+// the library to decide.
+pub(crate) fn find_output_format(formats: &[OutputFormat], name: &str) -> Option<OutputFormat> {
+    if formats.is_empty() {
+        return Some(OutputFormat {
+            name: name.to_uppercase(),
+            description: String::new(),
+            mime_type: String::new(),
+        });
+    }
+    formats
+        .iter()
+        .find(|x| x.name.eq_ignore_ascii_case(name))
+        .cloned()
+}
+
+impl MediaInfo {
+    pub fn version(&mut self) -> MediaInfoResult<LibraryVersion> {
+        let version = self.option("Info_Version", "")?;
+        LibraryVersion::parse(&version).ok_or(MediaInfoError::NonNumericResult)
+    }
+
+    /// Fails with `VersionMismatch` when the linked library is not the one
+    /// vendored in `mediainfo_src`.
+    pub fn check_version(&mut self) -> MediaInfoResult<LibraryVersion> {
+        let linked = self.version()?;
+        let vendored = LibraryVersion::vendored();
+
+        if linked != vendored {
+            return Err(MediaInfoError::VersionMismatch { linked, vendored });
+        }
+
+        Ok(linked)
+    }
+
+    pub fn codecs(&mut self) -> MediaInfoResult<Vec<Codec>> {
+        Ok(parse_codecs(&self.option("Info_Codecs", "")?))
+    }
+
+    pub fn output_formats(&mut self) -> MediaInfoResult<Vec<OutputFormat>> {
+        Ok(parse_output_formats(
+            &self.option("Info_OutputFormats_CSV", "")?,
+        ))
+    }
+
+    /// The output format called `name`, ignoring case, or `None` when the
+    /// library does not have it.
+    pub fn output_format(&mut self, name: &str) -> MediaInfoResult<Option<OutputFormat>> {
+        let formats = match self.output_formats() {
+            Ok(formats) => formats,
+            Err(MediaInfoError::ZeroLengthResult) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(find_output_format(&formats, name))
+    }
+
+    /// Whether URLs can be passed to `open()`; needs libcurl at runtime.
+    pub fn can_handle_urls(&mut self) -> bool {
+        PluginState::from_option(self.option("Info_CanHandleUrls", "")) == PluginState::Available
+    }
+
+    /// State of the Graphviz plugin used for SVG graph output.
+    pub fn svg_plugin_state(&mut self) -> PluginState {
+        PluginState::from_option(self.option("Info_Graph_Svg_Plugin_State", ""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_library_versions() {
+        assert_eq!(
+            Some(LibraryVersion {
+                major: 25,
+                minor: 7,
+                patch: 0
+            }),
+            LibraryVersion::parse("MediaInfoLib - v25.07")
+        );
+        assert_eq!(
+            Some(LibraryVersion {
+                major: 24,
+                minor: 12,
+                patch: 1
+            }),
+            LibraryVersion::parse("MediaInfoLib - v24.12.1 modified")
+        );
+        assert_eq!(None, LibraryVersion::parse("MediaInfoLib - vX"));
+        assert_eq!("25.07", LibraryVersion::parse("25.07").unwrap().to_string());
+        assert!(LibraryVersion::parse("25.07") > LibraryVersion::parse("24.12.1"));
+    }
+
+    #[test]
+    fn vendored_version_is_valid() {
+        assert!(LibraryVersion::parse(VENDORED_VERSION).is_some());
+    }
+
+    #[test]
+    fn parses_codec_table() {
+        let codecs = parse_codecs(
+            "avc1;AVC;4CC;V;AVC;;Advanced Video Codec;http://example.com\n2000;AC-3;2CC;A;AC3;CBR\n",
+        );
+
+        assert_eq!(codecs.len(), 2);
+        assert_eq!(codecs[0].id, "avc1");
+        assert_eq!(codecs[0].kind_of_stream, "V");
+        assert_eq!(codecs[0].url, "http://example.com");
+        assert_eq!(codecs[1].bit_rate_mode, "CBR");
+        assert_eq!(codecs[1].description, "");
+    }
+
+    #[test]
+    fn parses_output_formats() {
+        let formats =
+            parse_output_formats("Text,Text,text/plain\nEBUCore_1.6,\"EBUCore, 1.6\",text/xml\n");
+
+        assert_eq!(formats.len(), 2);
+        assert_eq!(formats[0].name, "Text");
+        assert_eq!(formats[1].description, "EBUCore, 1.6");
+        assert_eq!(formats[1].mime_type, "text/xml");
+
+        let found = find_output_format(&formats, "ebucore_1.6").unwrap();
+        assert_eq!(found.name, "EBUCore_1.6");
+        assert_eq!(find_output_format(&formats, "PBCore"), None);
+        assert_eq!(find_output_format(&[], "pbcore").unwrap().name, "PBCORE");
+    }
+
+    #[test]
+    fn maps_plugin_states() {
+        assert_eq!(
+            PluginState::Available,
+            PluginState::from_option(Ok("1".to_string()))
+        );
+        assert_eq!(
+            PluginState::Unavailable,
+            PluginState::from_option(Ok("0".to_string()))
+        );
+        assert_eq!(
+            PluginState::Disabled,
+            PluginState::from_option(Ok(
+                "Graphviz support is disabled due to compilation options".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,604 @@
+// Speaker layouts of audio streams.
+//
+// `ChannelLayout` lists one label per channel in stream order, using the
+// names MediaInfo normalised to in 2018 ("L R C LFE Ls Rs Lb Rb", "Tfl" for
+// top front left...). Older names and the ones of a few parsers are mapped
+// to the same speakers. When only `ChannelPositions/String2` ("3/2/0.1") is
+// available the layout is derived from it.
+
+use crate::parsing::ListItem;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Speaker {
+    L,
+    R,
+    C,
+    Lfe,
+    Lfe2,
+    Lfe3,
+    /// Left surround; the side pair once back speakers are present.
+    Ls,
+    Rs,
+    Lb,
+    Rb,
+    Cb,
+    Lc,
+    Rc,
+    Lw,
+    Rw,
+    Lscr,
+    Rscr,
+    Tfl,
+    Tfr,
+    Tfc,
+    Tc,
+    Tsl,
+    Tsr,
+    Tbl,
+    Tbr,
+    Tbc,
+    Bfl,
+    Bfr,
+    Bfc,
+    /// Matrix encoded left total (Dolby Surround, Pro Logic).
+    Lt,
+    Rt,
+    /// A channel without a position, e.g. "M" for mono programmes.
+    Mono,
+    Other(String),
+}
+
+impl Speaker {
+    pub fn from_label(label: &str) -> Speaker {
+        match label {
+            "L" | "FL" => Speaker::L,
+            "R" | "FR" => Speaker::R,
+            "C" | "FC" => Speaker::C,
+            "LFE" | "SW" => Speaker::Lfe,
+            "LFE2" => Speaker::Lfe2,
+            "LFE3" => Speaker::Lfe3,
+            "Ls" | "LS" | "SL" | "Cl" | "CL" => Speaker::Ls,
+            "Rs" | "RS" | "SR" | "Cr" | "CR" => Speaker::Rs,
+            "Lb" | "BL" | "Lrs" | "Lsr" => Speaker::Lb,
+            "Rb" | "BR" | "Rrs" | "Rsr" => Speaker::Rb,
+            "Cb" | "BC" | "Cs" | "S" => Speaker::Cb,
+            "Lc" | "LC" | "FLC" => Speaker::Lc,
+            "Rc" | "RC" | "FRC" => Speaker::Rc,
+            "Lw" => Speaker::Lw,
+            "Rw" => Speaker::Rw,
+            "Lscr" | "Lsc" => Speaker::Lscr,
+            "Rscr" | "Rsc" => Speaker::Rscr,
+            "Tfl" | "TFL" | "Lh" | "Lv" | "Lvh" | "Lfh" | "Lhs" | "Ltf" => Speaker::Tfl,
+            "Tfr" | "TFR" | "Rh" | "Rv" | "Rvh" | "Rfh" | "Rhs" | "Rtf" => Speaker::Tfr,
+            "Tfc" | "TFC" | "Ch" | "Cv" | "Vhc" => Speaker::Tfc,
+            "Tc" | "TC" | "Ts" | "Oh" => Speaker::Tc,
+            "Tsl" | "Lts" | "Ltm" | "Lvss" => Speaker::Tsl,
+            "Tsr" | "Rts" | "Rtm" | "Rvss" => Speaker::Tsr,
+            "Tbl" | "TBL" | "Lhr" | "Lrh" | "Lvr" | "Ltr" => Speaker::Tbl,
+            "Tbr" | "TBR" | "Rhr" | "Rrh" | "Rvr" | "Rtr" => Speaker::Tbr,
+            "Tbc" | "TBC" | "Chr" | "Cvr" => Speaker::Tbc,
+            "Bfl" | "LI" => Speaker::Bfl,
+            "Bfr" | "RI" => Speaker::Bfr,
+            "Bfc" | "CI" => Speaker::Bfc,
+            "Lt" => Speaker::Lt,
+            "Rt" => Speaker::Rt,
+            "M" => Speaker::Mono,
+            other => Speaker::Other(other.to_string()),
+        }
+    }
+
+    pub fn is_lfe(&self) -> bool {
+        matches!(self, Speaker::Lfe | Speaker::Lfe2 | Speaker::Lfe3)
+    }
+
+    /// Speakers above the listener.
+    pub fn is_height(&self) -> bool {
+        matches!(
+            self,
+            Speaker::Tfl
+                | Speaker::Tfr
+                | Speaker::Tfc
+                | Speaker::Tc
+                | Speaker::Tsl
+                | Speaker::Tsr
+                | Speaker::Tbl
+                | Speaker::Tbr
+                | Speaker::Tbc
+        )
+    }
+
+    /// Speakers below the listener.
+    pub fn is_bottom(&self) -> bool {
+        matches!(self, Speaker::Bfl | Speaker::Bfr | Speaker::Bfc)
+    }
+
+    /// The ffmpeg channel name, e.g. "FL" or "TBR".
+    pub fn ffmpeg_name(&self) -> Option<&'static str> {
+        Some(match self {
+            Speaker::L => "FL",
+            Speaker::R => "FR",
+            Speaker::C | Speaker::Mono => "FC",
+            Speaker::Lfe => "LFE",
+            Speaker::Lfe2 => "LFE2",
+            Speaker::Ls => "SL",
+            Speaker::Rs => "SR",
+            Speaker::Lb => "BL",
+            Speaker::Rb => "BR",
+            Speaker::Cb => "BC",
+            Speaker::Lc => "FLC",
+            Speaker::Rc => "FRC",
+            Speaker::Lw => "WL",
+            Speaker::Rw => "WR",
+            Speaker::Tfl => "TFL",
+            Speaker::Tfr => "TFR",
+            Speaker::Tfc => "TFC",
+            Speaker::Tc => "TC",
+            Speaker::Tsl => "TSL",
+            Speaker::Tsr => "TSR",
+            Speaker::Tbl => "TBL",
+            Speaker::Tbr => "TBR",
+            Speaker::Tbc => "TBC",
+            Speaker::Bfl => "BFL",
+            Speaker::Bfr => "BFR",
+            Speaker::Bfc => "BFC",
+            Speaker::Lt => "DL",
+            Speaker::Rt => "DR",
+            Speaker::Lfe3 | Speaker::Lscr | Speaker::Rscr | Speaker::Other(_) => return None,
+        })
+    }
+
+    /// The `dwChannelMask` bit of WAVE_FORMAT_EXTENSIBLE.
+    pub fn wave_mask(&self) -> Option<u32> {
+        Some(match self {
+            Speaker::L => 0x1,
+            Speaker::R => 0x2,
+            Speaker::C | Speaker::Mono => 0x4,
+            Speaker::Lfe => 0x8,
+            Speaker::Lb => 0x10,
+            Speaker::Rb => 0x20,
+            Speaker::Lc => 0x40,
+            Speaker::Rc => 0x80,
+            Speaker::Cb => 0x100,
+            Speaker::Ls => 0x200,
+            Speaker::Rs => 0x400,
+            Speaker::Tc => 0x800,
+            Speaker::Tfl => 0x1000,
+            Speaker::Tfc => 0x2000,
+            Speaker::Tfr => 0x4000,
+            Speaker::Tbl => 0x8000,
+            Speaker::Tbc => 0x10000,
+            Speaker::Tbr => 0x20000,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for Speaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Speaker::Lfe => "LFE",
+            Speaker::Lfe2 => "LFE2",
+            Speaker::Lfe3 => "LFE3",
+            Speaker::Mono => "M",
+            Speaker::Other(label) => label,
+            other => return write!(f, "{other:?}"),
+        };
+        f.write_str(label)
+    }
+}
+
+// ffmpeg's named layouts, by channel set.
+const FFMPEG_LAYOUTS: &[(&str, &[&str])] = &[
+    ("mono", &["FC"]),
+    ("stereo", &["FL", "FR"]),
+    ("downmix", &["DL", "DR"]),
+    ("2.1", &["FL", "FR", "LFE"]),
+    ("3.0", &["FL", "FR", "FC"]),
+    ("3.0(back)", &["FL", "FR", "BC"]),
+    ("4.0", &["FL", "FR", "FC", "BC"]),
+    ("quad", &["FL", "FR", "BL", "BR"]),
+    ("quad(side)", &["FL", "FR", "SL", "SR"]),
+    ("3.1", &["FL", "FR", "FC", "LFE"]),
+    ("5.0", &["FL", "FR", "FC", "BL", "BR"]),
+    ("5.0(side)", &["FL", "FR", "FC", "SL", "SR"]),
+    ("4.1", &["FL", "FR", "FC", "LFE", "BC"]),
+    ("5.1", &["FL", "FR", "FC", "LFE", "BL", "BR"]),
+    ("5.1(side)", &["FL", "FR", "FC", "LFE", "SL", "SR"]),
+    ("6.0", &["FL", "FR", "FC", "BC", "SL", "SR"]),
+    ("hexagonal", &["FL", "FR", "FC", "BL", "BR", "BC"]),
+    ("6.1", &["FL", "FR", "FC", "LFE", "BC", "SL", "SR"]),
+    ("6.1(back)", &["FL", "FR", "FC", "LFE", "BL", "BR", "BC"]),
+    ("7.0", &["FL", "FR", "FC", "BL", "BR", "SL", "SR"]),
+    ("7.1", &["FL", "FR", "FC", "LFE", "BL", "BR", "SL", "SR"]),
+    (
+        "7.1(wide)",
+        &["FL", "FR", "FC", "LFE", "BL", "BR", "FLC", "FRC"],
+    ),
+    (
+        "7.1(wide-side)",
+        &["FL", "FR", "FC", "LFE", "FLC", "FRC", "SL", "SR"],
+    ),
+    (
+        "5.1.2",
+        &["FL", "FR", "FC", "LFE", "BL", "BR", "TFL", "TFR"],
+    ),
+    (
+        "5.1.4",
+        &[
+            "FL", "FR", "FC", "LFE", "BL", "BR", "TFL", "TFR", "TBL", "TBR",
+        ],
+    ),
+    (
+        "7.1.2",
+        &[
+            "FL", "FR", "FC", "LFE", "BL", "BR", "SL", "SR", "TFL", "TFR",
+        ],
+    ),
+    (
+        "7.1.4",
+        &[
+            "FL", "FR", "FC", "LFE", "BL", "BR", "SL", "SR", "TFL", "TFR", "TBL", "TBR",
+        ],
+    ),
+];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChannelLayout {
+    /// One entry per channel, in stream order.
+    pub speakers: Vec<Speaker>,
+    /// Whether the stream carries audio objects (Dolby Atmos, ADM, IAB,
+    /// MPEG-H) on top of, or instead of, the channel bed.
+    pub object_based: bool,
+    /// Number of objects, when the format reports it.
+    pub objects: Option<u32>,
+}
+
+impl ChannelLayout {
+    /// Parses a `ChannelLayout` value, e.g. "L R C LFE Ls Rs".
+    pub fn parse(layout: &str) -> Option<ChannelLayout> {
+        let speakers: Vec<Speaker> = layout
+            .split(" / ")
+            .next()?
+            .split_whitespace()
+            .map(Speaker::from_label)
+            .collect();
+        if speakers.is_empty() {
+            return None;
+        }
+
+        Some(ChannelLayout {
+            speakers,
+            object_based: false,
+            objects: None,
+        })
+    }
+
+    /// Derives a layout from `ChannelPositions/String2`, e.g. "3/2/0.1"
+    /// (front / side / back . LFE).
+    pub fn from_positions(positions: &str) -> Option<ChannelLayout> {
+        let positions = positions.split(" / ").next()?.trim();
+        let (groups, lfe) = positions.split_once('.').unwrap_or((positions, "0"));
+        let counts: Vec<u32> = groups
+            .split('/')
+            .map(|n| n.trim().parse().ok())
+            .collect::<Option<_>>()?;
+        let lfe: u32 = lfe.trim().parse().ok()?;
+
+        let front = counts.first().copied().unwrap_or(0);
+        let side = counts.get(1).copied().unwrap_or(0);
+        let back = counts.get(2).copied().unwrap_or(0);
+
+        let mut speakers = match front {
+            1 => vec![Speaker::C],
+            2 => vec![Speaker::L, Speaker::R],
+            3 => vec![Speaker::L, Speaker::R, Speaker::C],
+            5 => vec![Speaker::L, Speaker::R, Speaker::C, Speaker::Lc, Speaker::Rc],
+            0 => Vec::new(),
+            _ => return None,
+        };
+        speakers.extend([Speaker::Lfe, Speaker::Lfe2].into_iter().take(lfe as usize));
+        match side {
+            0 => {}
+            1 => speakers.push(Speaker::Cb),
+            2 => speakers.extend([Speaker::Ls, Speaker::Rs]),
+            _ => return None,
+        }
+        match back {
+            0 => {}
+            1 => speakers.push(Speaker::Cb),
+            2 => speakers.extend([Speaker::Lb, Speaker::Rb]),
+            3 => speakers.extend([Speaker::Lb, Speaker::Rb, Speaker::Cb]),
+            _ => return None,
+        }
+
+        if speakers.is_empty() {
+            return None;
+        }
+        Some(ChannelLayout {
+            speakers,
+            object_based: false,
+            objects: None,
+        })
+    }
+
+    /// Parses a `ChannelPositions` value, e.g. "Front: L C R, Side: L R,
+    /// LFE". "Object Based" is a layout of objects without a bed.
+    pub fn from_position_names(positions: &str) -> Option<ChannelLayout> {
+        let positions = positions.trim();
+        if positions == "Object Based" {
+            return Some(ChannelLayout {
+                speakers: Vec::new(),
+                object_based: true,
+                objects: None,
+            });
+        }
+
+        let mut speakers = Vec::new();
+        for group in positions.split(',').map(str::trim) {
+            let (area, labels) = group.split_once(':').unwrap_or(("", group));
+            for label in labels.split_whitespace() {
+                speakers.push(match (area.trim(), label) {
+                    ("Front" | "", "L") => Speaker::L,
+                    ("Front" | "", "R") => Speaker::R,
+                    ("Front" | "", "C") => Speaker::C,
+                    ("Side", "L") => Speaker::Ls,
+                    ("Side", "R") => Speaker::Rs,
+                    ("Side" | "Back", "C") => Speaker::Cb,
+                    ("Back", "L") => Speaker::Lb,
+                    ("Back", "R") => Speaker::Rb,
+                    ("High" | "Top", "L") => Speaker::Tfl,
+                    ("High" | "Top", "R") => Speaker::Tfr,
+                    ("High" | "Top", "C") => Speaker::Tfc,
+                    ("", label) => Speaker::from_label(label),
+                    (area, label) => Speaker::Other(format!("{area}: {label}")),
+                });
+            }
+        }
+
+        if speakers.is_empty() {
+            return None;
+        }
+        Some(ChannelLayout {
+            speakers,
+            object_based: false,
+            objects: None,
+        })
+    }
+
+    /// Builds the layout of an audio stream from a field lookup returning
+    /// `None` for empty fields.
+    pub(crate) fn from_fields(get: impl Fn(&str) -> Option<String>) -> Option<ChannelLayout> {
+        let objects = get("NumberOfDynamicObjects")
+            .or_else(|| get("NumberOfObjects"))
+            .and_then(|n| n.split(" / ").next()?.trim().parse().ok());
+        let object_based = objects.is_some()
+            || get("Format_AdditionalFeatures").is_some_and(|f| f.contains("JOC"))
+            || get("Format_Commercial_IfAny").is_some_and(|f| f.contains("Atmos"))
+            || get("Format").is_some_and(|f| matches!(f.as_str(), "ADM" | "IAB"));
+
+        let layout = get("ChannelLayout")
+            .as_deref()
+            .and_then(ChannelLayout::parse)
+            .or_else(|| {
+                get("ChannelPositions/String2")
+                    .as_deref()
+                    .and_then(ChannelLayout::from_positions)
+            })
+            .or_else(|| match get("Channel(s)").as_deref() {
+                Some("1") => ChannelLayout::parse("C"),
+                Some("2") => ChannelLayout::parse("L R"),
+                _ => None,
+            });
+
+        match layout {
+            Some(layout) => Some(ChannelLayout {
+                object_based,
+                objects,
+                ..layout
+            }),
+            None if object_based => Some(ChannelLayout {
+                speakers: Vec::new(),
+                object_based,
+                objects,
+            }),
+            None => None,
+        }
+    }
+
+    pub fn channels(&self) -> usize {
+        self.speakers.len()
+    }
+
+    pub fn has_lfe(&self) -> bool {
+        self.speakers.iter().any(Speaker::is_lfe)
+    }
+
+    pub fn is_mono(&self) -> bool {
+        matches!(self.speakers.as_slice(), [Speaker::C] | [Speaker::Mono])
+    }
+
+    /// A left/right pair, discrete or matrix encoded.
+    pub fn is_stereo(&self) -> bool {
+        let has = |speaker: Speaker| self.speakers.contains(&speaker);
+        self.channels() == 2
+            && ((has(Speaker::L) && has(Speaker::R)) || (has(Speaker::Lt) && has(Speaker::Rt)))
+    }
+
+    /// The usual "surround.LFE.height" notation: "2.0", "5.1", "7.1.4".
+    pub fn notation(&self) -> String {
+        let lfe = self.speakers.iter().filter(|s| s.is_lfe()).count();
+        let height = self.speakers.iter().filter(|s| s.is_height()).count();
+        let surround = self.channels() - lfe - height;
+
+        if height > 0 {
+            format!("{surround}.{lfe}.{height}")
+        } else {
+            format!("{surround}.{lfe}")
+        }
+    }
+
+    /// "mono", "stereo", or the channel notation for larger layouts, with
+    /// " + objects" for object-based audio.
+    pub fn classification(&self) -> String {
+        let bed = if self.speakers.is_empty() {
+            String::new()
+        } else if self.is_mono() {
+            "mono".to_string()
+        } else if self.is_stereo() {
+            "stereo".to_string()
+        } else {
+            self.notation()
+        };
+
+        match (self.object_based, bed.is_empty()) {
+            (true, true) => "objects".to_string(),
+            (true, false) => format!("{bed} + objects"),
+            (false, _) => bed,
+        }
+    }
+
+    /// The ffmpeg layout name, e.g. "5.1(side)", or the "FL+FR+LFE" form
+    /// when it has no name. `None` when a speaker has no ffmpeg channel.
+    pub fn ffmpeg_layout(&self) -> Option<String> {
+        let names: Vec<&str> = self
+            .speakers
+            .iter()
+            .map(Speaker::ffmpeg_name)
+            .collect::<Option<_>>()?;
+        if names.is_empty() {
+            return None;
+        }
+
+        let mut sorted = names.clone();
+        sorted.sort_unstable();
+        let named = FFMPEG_LAYOUTS.iter().find(|(_, channels)| {
+            let mut channels = channels.to_vec();
+            channels.sort_unstable();
+            channels == sorted
+        });
+
+        Some(match named {
+            Some((name, _)) => name.to_string(),
+            None => names.join("+"),
+        })
+    }
+
+    /// The WAVE_FORMAT_EXTENSIBLE `dwChannelMask`. `None` when a speaker
+    /// has no WAVE position or appears twice.
+    pub fn wave_channel_mask(&self) -> Option<u32> {
+        let mut mask = 0;
+        for speaker in &self.speakers {
+            let bit = speaker.wave_mask()?;
+            if mask & bit != 0 {
+                return None;
+            }
+            mask |= bit;
+        }
+        Some(mask)
+    }
+}
+
+// The layers of a `ChannelPositions` field. An entry that names no speaker
+// is an empty layout.
+impl ListItem for ChannelLayout {
+    fn from_item(item: &str) -> ChannelLayout {
+        ChannelLayout::from_position_names(item).unwrap_or(ChannelLayout {
+            speakers: Vec::new(),
+            object_based: false,
+            objects: None,
+        })
+    }
+}
+
+impl fmt::Display for ChannelLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labels: Vec<String> = self.speakers.iter().map(|s| s.to_string()).collect();
+        f.write_str(&labels.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::AudioSnapshot;
+
+    fn layout(fields: &[(&str, &str)]) -> Option<ChannelLayout> {
+        let audio = AudioSnapshot::from_pairs(0, fields);
+        ChannelLayout::from_fields(|name| audio.get(name).map(str::to_string))
+    }
+
+    #[test]
+    fn classifies_channel_layouts() {
+        let cases = [
+            ("M", "mono", "mono", 0x4),
+            ("L R", "stereo", "stereo", 0x3),
+            ("L R C LFE Ls Rs", "5.1", "5.1(side)", 0x60f),
+            ("L R C LFE Ls Rs Lb Rb", "7.1", "7.1", 0x63f),
+            (
+                "L R C LFE Ls Rs Lb Rb Tfl Tfr Tbl Tbr",
+                "7.1.4",
+                "7.1.4",
+                0x2d63f,
+            ),
+        ];
+
+        for (value, class, ffmpeg, mask) in cases {
+            let layout = ChannelLayout::parse(value).unwrap();
+            assert_eq!(layout.classification(), class, "{value}");
+            assert_eq!(layout.ffmpeg_layout().as_deref(), Some(ffmpeg), "{value}");
+            assert_eq!(layout.wave_channel_mask(), Some(mask), "{value}");
+            assert_eq!(layout.to_string(), value);
+        }
+    }
+
+    #[test]
+    fn maps_legacy_names_and_custom_layouts() {
+        let layout = ChannelLayout::parse("L R C LFE Lrs Rrs Lh Rh").unwrap();
+        assert_eq!(layout.to_string(), "L R C LFE Lb Rb Tfl Tfr");
+        assert_eq!(layout.ffmpeg_layout().as_deref(), Some("5.1.2"));
+
+        let custom = ChannelLayout::parse("L R LFE Cb").unwrap();
+        assert_eq!(custom.notation(), "3.1");
+        assert_eq!(custom.ffmpeg_layout().as_deref(), Some("FL+FR+LFE+BC"));
+
+        let unknown = ChannelLayout::parse("L R Xyz").unwrap();
+        assert_eq!(unknown.speakers[2], Speaker::Other("Xyz".to_string()));
+        assert_eq!(unknown.ffmpeg_layout(), None);
+        assert_eq!(unknown.wave_channel_mask(), None);
+    }
+
+    #[test]
+    fn derives_layouts_from_fields() {
+        let positions = layout(&[("ChannelPositions/String2", "3/2/0.1")]).unwrap();
+        assert_eq!(positions.to_string(), "L R C LFE Ls Rs");
+
+        let atmos = layout(&[
+            ("ChannelLayout", "L R C LFE Ls Rs Lb Rb"),
+            ("Format_AdditionalFeatures", "JOC"),
+            ("NumberOfDynamicObjects", "15"),
+        ])
+        .unwrap();
+        assert!(atmos.object_based);
+        assert_eq!(atmos.objects, Some(15));
+        assert_eq!(atmos.classification(), "7.1 + objects");
+
+        let adm = layout(&[("Format", "ADM")]).unwrap();
+        assert_eq!(adm.classification(), "objects");
+
+        let named = ChannelLayout::from_position_names("Front: L C R, Side: L R, LFE").unwrap();
+        assert_eq!(named.to_string(), "L C R Ls Rs LFE");
+        assert_eq!(named.notation(), "5.1");
+        assert!(
+            ChannelLayout::from_position_names("Object Based")
+                .unwrap()
+                .object_based
+        );
+        assert_eq!(ChannelLayout::from_position_names(""), None);
+
+        assert_eq!(layout(&[("Channel(s)", "2")]).unwrap().notation(), "2.0");
+        assert_eq!(layout(&[]), None);
+    }
+}
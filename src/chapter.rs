@@ -0,0 +1,190 @@
+// Chapters of menu streams.
+//
+// MediaInfo stores each chapter as an extra field of the menu stream, between
+// the `Chapters_Pos_Begin` and `Chapters_Pos_End` positions. The field name
+// is the start time ("00:05:12.480") and the value the title, prefixed with
+// its language when the container has one ("en:Opening"). Matroska joins the
+// titles of several languages with " - " ("en:Opening - fr:Ouverture").
+
+use crate::language::LanguageTag;
+use crate::parsing;
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChapterTitle {
+    pub title: String,
+    pub language: Option<LanguageTag>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Chapter {
+    pub start: Duration,
+    /// The first title.
+    pub title: String,
+    pub language: Option<LanguageTag>,
+    /// Every title, in the order the container lists them.
+    pub titles: Vec<ChapterTitle>,
+}
+
+impl Chapter {
+    /// Parses one chapter field. `None` when `name` is not a start time.
+    pub fn parse(name: &str, value: &str) -> Option<Chapter> {
+        let start = parse_start(name)?;
+        let titles = parse_titles(value);
+        let first = titles.first().cloned().unwrap_or(ChapterTitle {
+            title: String::new(),
+            language: None,
+        });
+
+        Some(Chapter {
+            start,
+            title: first.title,
+            language: first.language,
+            titles,
+        })
+    }
+
+    /// The title in the first language of `preferences` the chapter has,
+    /// or its first title.
+    pub fn title_for(&self, preferences: &[&str]) -> &str {
+        preferences
+            .iter()
+            .find_map(|range| {
+                self.titles
+                    .iter()
+                    .find(|t| t.language.as_ref().is_some_and(|l| l.matches(range)))
+            })
+            .map_or(&self.title, |t| &t.title)
+    }
+}
+
+/// The chapters among `fields`, sorted by start time.
+pub(crate) fn from_fields(fields: &[(String, String)]) -> Vec<Chapter> {
+    let mut chapters: Vec<Chapter> = fields
+        .iter()
+        .filter_map(|(name, value)| Chapter::parse(name, value))
+        .collect();
+    chapters.sort_by_key(|c| c.start);
+    chapters
+}
+
+/// Whether a field name is a chapter start time.
+pub(crate) fn is_chapter_field(name: &str) -> bool {
+    parse_start(name).is_some()
+}
+
+// "00:05:12.480", or "_00_05_12_480" as the JSON output spells it.
+fn parse_start(name: &str) -> Option<Duration> {
+    let name = name.trim();
+    let name = match name.strip_prefix('_') {
+        Some(rest) => {
+            let (clock, millis) = rest.rsplit_once('_')?;
+            format!("{}.{millis}", clock.replace('_', ":"))
+        }
+        None => name.to_string(),
+    };
+
+    if !name.starts_with(|c: char| c.is_ascii_digit()) || name.matches(':').count() != 2 {
+        return None;
+    }
+    parsing::parse_duration(&name)
+}
+
+fn parse_titles(value: &str) -> Vec<ChapterTitle> {
+    let mut titles: Vec<ChapterTitle> = Vec::new();
+
+    for part in value.split(" - ") {
+        match split_language(part) {
+            // " - " inside a title, not between two languages.
+            (None, _) if !titles.is_empty() && !part.starts_with(':') => {
+                let last = titles.last_mut().unwrap();
+                last.title.push_str(" - ");
+                last.title.push_str(part);
+            }
+            (language, title) => titles.push(ChapterTitle {
+                title: title.to_string(),
+                language,
+            }),
+        }
+    }
+
+    titles
+}
+
+// "en:Opening" -> (en, "Opening"), ":Opening" -> (None, "Opening").
+fn split_language(part: &str) -> (Option<LanguageTag>, &str) {
+    let Some((prefix, title)) = part.split_once(':') else {
+        return (None, part);
+    };
+    if prefix.is_empty() {
+        return (None, title);
+    }
+
+    let looks_like_code = prefix.len() <= 8
+        && prefix.starts_with(|c: char| c.is_ascii_alphabetic())
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    match looks_like_code
+        .then(|| LanguageTag::parse(prefix))
+        .flatten()
+    {
+        Some(language) => (Some(language), title),
+        None => (None, part),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_chapter_fields() {
+        let chapters = from_fields(&fields(&[
+            ("00:05:12.480", "en:Part 2 - fr:Partie 2"),
+            ("00:00:00.000", ":Intro"),
+            ("Chapters_Pos_Begin", "40"),
+        ]));
+
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].start, Duration::ZERO);
+        assert_eq!(chapters[0].title, "Intro");
+        assert_eq!(chapters[0].language, None);
+
+        let second = &chapters[1];
+        assert_eq!(second.start, Duration::from_millis(312_480));
+        assert_eq!(second.title, "Part 2");
+        assert_eq!(second.language.as_ref().unwrap().as_str(), "en");
+        assert_eq!(second.titles.len(), 2);
+        assert_eq!(second.title_for(&["fr", "en"]), "Partie 2");
+        assert_eq!(second.title_for(&["de"]), "Part 2");
+    }
+
+    #[test]
+    fn keeps_titles_without_languages_intact() {
+        let chapter = Chapter::parse("00:01:00.000", "Act 1 - The Beginning").unwrap();
+        assert_eq!(chapter.title, "Act 1 - The Beginning");
+
+        let chapter = Chapter::parse("00:02:00.000", "Note: read this").unwrap();
+        assert_eq!(chapter.title, "Note: read this");
+
+        for title in ["Act: One", "Day: 1", "End: Credits"] {
+            let chapter = Chapter::parse("00:04:00.000", title).unwrap();
+            assert_eq!(chapter.title, title);
+            assert_eq!(chapter.language, None);
+        }
+
+        let chapter = Chapter::parse("_00_03_00_500", "Chapter 3").unwrap();
+        assert_eq!(chapter.start, Duration::from_millis(180_500));
+        assert!(Chapter::parse("Title", "Movie").is_none());
+    }
+}
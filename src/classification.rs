@@ -0,0 +1,318 @@
+// High-level facts derived from the raw fields.
+//
+// The rules, applied to the first video stream (or the first image stream
+// when there is no video):
+//
+// - resolution: 8K from 7680 wide or 4320 high, UHD from 3840 or 2160, FHD
+//   from 1920 or 1080, HD from 1280 or 720, SD below. Either dimension is
+//   enough, so cropped "scope" films and anamorphic HDV classify as FHD.
+// - HDR: the format `HdrInfo` detects.
+// - interlaced: `ScanType` is anything but "Progressive" (MBAFF, mixed).
+// - variable frame rate: `FrameRate_Mode` is "VFR".
+// - stereoscopic: `MultiView_Count` of 2 or more.
+// - spherical: a `Spatial Format` or `Spatial ProjectionType` field, or a
+//   general `Format` of "Spherical Video".
+//
+// Immersive audio is read from every audio stream: Dolby Atmos from the
+// commercial name, the JOC feature or the 16-ch TrueHD profile, DTS:X from the
+// commercial name or the "X" extension, AC-4 and MPEG-H from the format.
+// A file is audio-only when it has audio but no video or image, and a still
+// image when it has images only.
+
+use crate::hdr::{HdrFormat, HdrInfo};
+use crate::parsing;
+use crate::streams::BaseStream;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ResolutionClass {
+    Sd,
+    Hd,
+    Fhd,
+    Uhd,
+    Uhd8k,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ImmersiveAudio {
+    DolbyAtmos,
+    DtsX,
+    Ac4,
+    MpegH,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MediaClass {
+    pub resolution: Option<ResolutionClass>,
+    /// `None` without video or image streams.
+    pub hdr: Option<HdrFormat>,
+    /// Every immersive format found, in stream order.
+    pub immersive_audio: Vec<ImmersiveAudio>,
+    pub interlaced: bool,
+    pub variable_frame_rate: bool,
+    pub stereoscopic: bool,
+    pub spherical: bool,
+    pub audio_only: bool,
+    pub still_image: bool,
+}
+
+impl ResolutionClass {
+    pub fn from_dimensions(width: u64, height: u64) -> ResolutionClass {
+        match (width, height) {
+            (w, h) if w >= 7680 || h >= 4320 => ResolutionClass::Uhd8k,
+            (w, h) if w >= 3840 || h >= 2160 => ResolutionClass::Uhd,
+            (w, h) if w >= 1920 || h >= 1080 => ResolutionClass::Fhd,
+            (w, h) if w >= 1280 || h >= 720 => ResolutionClass::Hd,
+            _ => ResolutionClass::Sd,
+        }
+    }
+}
+
+impl fmt::Display for ResolutionClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ResolutionClass::Sd => "SD",
+            ResolutionClass::Hd => "HD",
+            ResolutionClass::Fhd => "FHD",
+            ResolutionClass::Uhd => "UHD",
+            ResolutionClass::Uhd8k => "8K",
+        })
+    }
+}
+
+impl fmt::Display for ImmersiveAudio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ImmersiveAudio::DolbyAtmos => "Dolby Atmos",
+            ImmersiveAudio::DtsX => "DTS:X",
+            ImmersiveAudio::Ac4 => "AC-4",
+            ImmersiveAudio::MpegH => "MPEG-H 3D Audio",
+        })
+    }
+}
+
+impl MediaClass {
+    pub(crate) fn from_streams<G, V, A, I>(
+        general: &G,
+        video: &[V],
+        audio: &[A],
+        image: &[I],
+    ) -> MediaClass
+    where
+        G: BaseStream,
+        V: BaseStream,
+        A: BaseStream,
+        I: BaseStream,
+    {
+        let visual: Option<&dyn BaseStream> = match (video.first(), image.first()) {
+            (Some(x), _) => Some(x),
+            (None, Some(x)) => Some(x),
+            (None, None) => None,
+        };
+        let text = |stream: &dyn BaseStream, name: &str| stream.field(name).unwrap_or_default();
+        let number = |stream: &dyn BaseStream, name: &str| parsing::parse_u64(&text(stream, name));
+
+        let resolution = visual.and_then(|x| {
+            Some(ResolutionClass::from_dimensions(
+                number(x, "Width")?,
+                number(x, "Height")?,
+            ))
+        });
+        let hdr = visual.map(|x| HdrInfo::from_fields(|name| x.field(name).ok()).format);
+
+        let mut immersive_audio = Vec::new();
+        for stream in audio {
+            for format in immersive_formats(stream) {
+                if !immersive_audio.contains(&format) {
+                    immersive_audio.push(format);
+                }
+            }
+        }
+
+        let video = video.first().map(|x| x as &dyn BaseStream);
+        let interlaced = video.is_some_and(|x| {
+            let scan = text(x, "ScanType");
+            !scan.is_empty() && scan != "Progressive"
+        });
+        let variable_frame_rate = video.is_some_and(|x| text(x, "FrameRate_Mode") == "VFR");
+        let stereoscopic = video.is_some_and(|x| number(x, "MultiView_Count").unwrap_or(0) >= 2);
+        let spherical = text(general, "Format") == "Spherical Video"
+            || visual.is_some_and(|x| {
+                !text(x, "Spatial Format").is_empty()
+                    || !text(x, "Spatial ProjectionType").is_empty()
+            });
+
+        MediaClass {
+            resolution,
+            hdr,
+            immersive_audio,
+            interlaced,
+            variable_frame_rate,
+            stereoscopic,
+            spherical,
+            audio_only: visual.is_none() && !audio.is_empty(),
+            still_image: video.is_none() && audio.is_empty() && !image.is_empty(),
+        }
+    }
+
+    pub fn is_hdr(&self) -> bool {
+        self.hdr.is_some_and(|x| x != HdrFormat::Sdr)
+    }
+}
+
+fn immersive_formats(stream: &dyn BaseStream) -> Vec<ImmersiveAudio> {
+    let text = |name: &str| stream.field(name).unwrap_or_default();
+    let format = text("Format");
+    let commercial = text("Format_Commercial_IfAny");
+    let features = text("Format_AdditionalFeatures");
+    let has_feature = |x: &str| features.split_whitespace().any(|f| f == x);
+
+    let mut formats = Vec::new();
+    if commercial.contains("Dolby Atmos")
+        || has_feature("JOC")
+        || text("Format_Profile").contains("MLP FBA 16-ch")
+    {
+        formats.push(ImmersiveAudio::DolbyAtmos);
+    }
+    if commercial.contains("DTS:X") || (format.starts_with("DTS") && has_feature("X")) {
+        formats.push(ImmersiveAudio::DtsX);
+    }
+    if format == "AC-4" {
+        formats.push(ImmersiveAudio::Ac4);
+    }
+    if format.starts_with("MPEG-H") {
+        formats.push(ImmersiveAudio::MpegH);
+    }
+    formats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::{AudioSnapshot, GeneralSnapshot, ImageSnapshot, VideoSnapshot};
+    #[test]
+    fn classifies_uhd_hdr_movie_with_atmos() {
+        let class = MediaClass::from_streams(
+            &GeneralSnapshot::from_pairs(0, &[("Format", "Matroska")]),
+            &[VideoSnapshot::from_pairs(
+                0,
+                &[
+                    ("Format", "HEVC"),
+                    ("Width", "3840"),
+                    ("Height", "1600"),
+                    ("ScanType", "Progressive"),
+                    ("FrameRate_Mode", "CFR"),
+                    ("HDR_Format", "SMPTE ST 2086"),
+                    ("transfer_characteristics", "PQ"),
+                ],
+            )],
+            &[
+                AudioSnapshot::from_pairs(
+                    0,
+                    &[
+                        ("Format", "MLP FBA"),
+                        ("Format_Profile", "MLP FBA 16-ch / MLP FBA"),
+                    ],
+                ),
+                AudioSnapshot::from_pairs(
+                    0,
+                    &[("Format", "DTS"), ("Format_AdditionalFeatures", "XLL X")],
+                ),
+                AudioSnapshot::from_pairs(
+                    0,
+                    &[
+                        ("Format", "E-AC-3"),
+                        ("Format_AdditionalFeatures", "JOC"),
+                        (
+                            "Format_Commercial_IfAny",
+                            "Dolby Digital Plus with Dolby Atmos",
+                        ),
+                    ],
+                ),
+            ],
+            &[] as &[ImageSnapshot],
+        );
+
+        assert_eq!(class.resolution, Some(ResolutionClass::Uhd));
+        assert_eq!(class.hdr, Some(HdrFormat::Hdr10));
+        assert!(class.is_hdr());
+        assert_eq!(
+            class.immersive_audio,
+            [ImmersiveAudio::DolbyAtmos, ImmersiveAudio::DtsX]
+        );
+        assert!(!class.interlaced && !class.variable_frame_rate);
+        assert!(!class.audio_only && !class.still_image);
+    }
+
+    #[test]
+    fn classifies_broadcast_and_phone_video() {
+        let broadcast = MediaClass::from_streams(
+            &GeneralSnapshot::from_pairs(0, &[("Format", "MPEG-TS")]),
+            &[VideoSnapshot::from_pairs(
+                0,
+                &[
+                    ("Width", "720"),
+                    ("Height", "576"),
+                    ("ScanType", "Interlaced"),
+                    ("transfer_characteristics", "BT.601"),
+                ],
+            )],
+            &[AudioSnapshot::from_pairs(0, &[("Format", "AC-4")])],
+            &[] as &[ImageSnapshot],
+        );
+        assert_eq!(broadcast.resolution, Some(ResolutionClass::Sd));
+        assert_eq!(broadcast.hdr, Some(HdrFormat::Sdr));
+        assert!(broadcast.interlaced);
+        assert_eq!(broadcast.immersive_audio, [ImmersiveAudio::Ac4]);
+
+        let phone = MediaClass::from_streams(
+            &GeneralSnapshot::from_pairs(0, &[("Format", "MPEG-4")]),
+            &[VideoSnapshot::from_pairs(
+                0,
+                &[
+                    ("Width", "1440"),
+                    ("Height", "1080"),
+                    ("FrameRate_Mode", "VFR"),
+                    ("MultiView_Count", "2"),
+                    ("Spatial ProjectionType", "Equirectangular"),
+                ],
+            )],
+            &[AudioSnapshot::from_pairs(0, &[("Format", "AAC")])],
+            &[] as &[ImageSnapshot],
+        );
+        assert_eq!(phone.resolution, Some(ResolutionClass::Fhd));
+        assert!(phone.variable_frame_rate && phone.stereoscopic && phone.spherical);
+        assert!(phone.immersive_audio.is_empty());
+    }
+
+    #[test]
+    fn classifies_audio_only_and_still_images() {
+        let music = MediaClass::from_streams(
+            &GeneralSnapshot::from_pairs(0, &[("Format", "FLAC")]),
+            &[] as &[VideoSnapshot],
+            &[AudioSnapshot::from_pairs(0, &[("Format", "FLAC")])],
+            &[] as &[ImageSnapshot],
+        );
+        assert!(music.audio_only && !music.still_image);
+        assert_eq!(music.resolution, None);
+        assert_eq!(music.hdr, None);
+
+        let photo = MediaClass::from_streams(
+            &GeneralSnapshot::from_pairs(0, &[("Format", "JPEG")]),
+            &[] as &[VideoSnapshot],
+            &[] as &[AudioSnapshot],
+            &[ImageSnapshot::from_pairs(
+                0,
+                &[("Width", "8192"), ("Height", "5464")],
+            )],
+        );
+        assert!(photo.still_image && !photo.audio_only);
+        assert_eq!(photo.resolution, Some(ResolutionClass::Uhd8k));
+        assert_eq!(
+            ResolutionClass::from_dimensions(1280, 534).to_string(),
+            "HD"
+        );
+    }
+}
@@ -1,9 +1,26 @@
+use crate::batch::{self, OpenError};
+use crate::capabilities::{Codec, LibraryVersion, OutputFormat, PluginState};
+use crate::chapter::Chapter;
+use crate::classification::MediaClass;
+use crate::date::MediaDate;
+use crate::diff::{DiffOptions, MediaDiff};
 use crate::ffi::{MediaInfo, MediaInfoResult, MediaInfoStream};
+use crate::field_catalog::FieldCatalog;
+use crate::format::{FormatName, FormatProfile};
+use crate::inform_template::InformTemplate;
+use crate::language::LanguageTag;
+use crate::policy::{Policy, PolicyReport};
+use crate::program::{Program, ProgramTrack};
+use crate::report::MediaInfoReport;
+use crate::snapshot::MediaSnapshot;
 use crate::streams::{
-    AudioStream, GeneralStream, ImageStream, MenuStream, OtherStream, TextStream, VideoStream,
+    AudioStream, BaseStream, GeneralStream, ImageStream, MenuStream, OtherStream, TextStream,
+    VideoStream,
 };
+use crate::timecode::Timecode;
+use crate::track_selection::{TrackSelection, TrackSelector};
 
-use chrono::{DateTime, Utc};
+use std::io::{self, Read};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -54,6 +71,14 @@ impl MediaInfoWrapper {
         }
     }
 
+    /// Opens `path` like `open`, but only succeeds when MediaInfo recognized
+    /// a format, and tells unreadable files from unsupported ones.
+    pub fn open_file(&mut self, path: &Path) -> Result<(), OpenError> {
+        batch::open_file(&mut self.handle.lock().unwrap(), path)?;
+        self.wrap_streams();
+        Ok(())
+    }
+
     pub fn open_data(&mut self, data: &[u8]) -> Result<(), String> {
         let data_len = data.len();
         if data_len == 0 {
@@ -77,6 +102,48 @@ impl MediaInfoWrapper {
         Ok(())
     }
 
+    /// Analyzes `size` bytes from `reader` through the buffer API, a chunk at
+    /// a time rather than all in memory. Jumps forward that MediaInfo asks
+    /// for skip data; a jump backwards cannot be served from a stream, so the
+    /// analysis then ends with what was read so far.
+    pub fn open_reader(&mut self, mut reader: impl Read, size: u64) -> io::Result<()> {
+        let mut handle = self.handle.lock().unwrap();
+        handle.open_buffer_init(size, 0);
+
+        let mut chunk = vec![0; 64 * 1024];
+        let mut offset = 0;
+        loop {
+            let read = reader.read(&mut chunk)?;
+            if read == 0 {
+                break;
+            }
+            offset += read as u64;
+            // Bit 3 is set once MediaInfo has all it needs.
+            if handle.open_buffer_continue(&chunk[..read]) & 0x08 != 0 {
+                break;
+            }
+
+            let goto = handle.open_buffer_continue_goto_get();
+            if goto == usize::MAX {
+                continue;
+            }
+            let goto = goto as u64;
+            if goto < offset {
+                break;
+            }
+            offset += io::copy(&mut reader.by_ref().take(goto - offset), &mut io::sink())?;
+            handle.open_buffer_init(size, offset);
+        }
+        handle.open_buffer_finalize();
+        drop(handle);
+
+        self.wrap_streams();
+        Ok(())
+    }
+
+    /// Most options, `Output` and `Complete` included, apply to every handle
+    /// in the process. Use `inform_with_options()` to change them for one
+    /// call.
     pub fn option(&mut self, parameter: &str, value: &str) -> MediaInfoResult<String> {
         self.handle.lock().unwrap().option(parameter, value)
     }
@@ -85,10 +152,58 @@ impl MediaInfoWrapper {
         self.handle.lock().unwrap().inform()
     }
 
+    /// Returns `inform()` with `options` set for this call only, e.g.
+    /// `[("Output", "XML")]`. The previous values are restored afterwards,
+    /// and concurrent calls through here or `get_full_inform_json()` wait
+    /// for each other instead of mixing their settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnknownParameter` for options that cannot be restored, or
+    /// any error from `inform()`.
+    pub fn inform_with_options(&mut self, options: &[(&str, &str)]) -> MediaInfoResult<String> {
+        self.handle
+            .lock()
+            .unwrap()
+            .with_options(options, |handle| handle.inform())
+    }
+
     pub fn available_parameters(&mut self) -> MediaInfoResult<String> {
         self.handle.lock().unwrap().available_parameters()
     }
 
+    pub fn field_catalog(&mut self) -> MediaInfoResult<FieldCatalog> {
+        self.handle.lock().unwrap().field_catalog()
+    }
+
+    pub fn version(&mut self) -> MediaInfoResult<LibraryVersion> {
+        self.handle.lock().unwrap().version()
+    }
+
+    pub fn check_version(&mut self) -> MediaInfoResult<LibraryVersion> {
+        self.handle.lock().unwrap().check_version()
+    }
+
+    pub fn codecs(&mut self) -> MediaInfoResult<Vec<Codec>> {
+        self.handle.lock().unwrap().codecs()
+    }
+
+    pub fn output_formats(&mut self) -> MediaInfoResult<Vec<OutputFormat>> {
+        self.handle.lock().unwrap().output_formats()
+    }
+
+    pub fn output_format(&mut self, name: &str) -> MediaInfoResult<Option<OutputFormat>> {
+        self.handle.lock().unwrap().output_format(name)
+    }
+
+    pub fn can_handle_urls(&mut self) -> bool {
+        self.handle.lock().unwrap().can_handle_urls()
+    }
+
+    pub fn svg_plugin_state(&mut self) -> PluginState {
+        self.handle.lock().unwrap().svg_plugin_state()
+    }
+
     pub fn close(&mut self) {
         self.general_stream.handler = None;
         self.video_streams = None;
@@ -209,6 +324,11 @@ impl MediaInfoWrapper {
         }
     }
 
+    /// Every general field, including those the wrapper does not delegate.
+    pub fn general_stream(&self) -> &GeneralStream {
+        &self.general_stream
+    }
+
     pub fn video_streams(&self) -> Option<&Vec<VideoStream>> {
         self.video_streams.as_ref()
     }
@@ -233,6 +353,160 @@ impl MediaInfoWrapper {
         self.menu_streams.as_ref()
     }
 
+    /// Audio streams whose language matches `range`, e.g. "en" matches
+    /// "en", "eng" and "en-US" tracks. See `LanguageTag::matches`.
+    pub fn audio_streams_with_language(&self, range: &str) -> Vec<&AudioStream> {
+        with_language(self.audio_streams.as_ref(), range)
+    }
+
+    /// Text streams whose language matches `range`.
+    pub fn text_streams_with_language(&self, range: &str) -> Vec<&TextStream> {
+        with_language(self.text_streams.as_ref(), range)
+    }
+
+    /// Menu streams whose language matches `range`.
+    pub fn menu_streams_with_language(&self, range: &str) -> Vec<&MenuStream> {
+        with_language(self.menu_streams.as_ref(), range)
+    }
+
+    /// The first audio stream matching the earliest possible entry of
+    /// `preferences`, a list of language ranges in order of preference.
+    pub fn preferred_audio_stream(&self, preferences: &[&str]) -> Option<&AudioStream> {
+        preferred(self.audio_streams.as_ref(), preferences)
+    }
+
+    /// The first text stream matching the earliest possible entry of
+    /// `preferences`.
+    pub fn preferred_text_stream(&self, preferences: &[&str]) -> Option<&TextStream> {
+        preferred(self.text_streams.as_ref(), preferences)
+    }
+
+    /// The chapters of the first menu stream that has any, sorted by start
+    /// time. Empty when the file has no chapters.
+    pub fn chapters(&self) -> Vec<Chapter> {
+        self.menu_streams
+            .iter()
+            .flatten()
+            .map(MenuStream::chapters)
+            .find(|chapters| !chapters.is_empty())
+            .unwrap_or_default()
+    }
+
+    /// The timecode of the first frame of the file. Taken from the MXF
+    /// material package when there is one, then from any other timecode
+    /// track, and finally from the video streams.
+    pub fn start_timecode(&self) -> Option<Timecode> {
+        let mut tracks: Vec<&OtherStream> = self
+            .other_streams
+            .iter()
+            .flatten()
+            .filter(|x| x.type_().is_ok_and(|t| t == "Time code"))
+            .collect();
+        tracks.sort_by_key(|x| match x.time_code_settings().as_deref() {
+            Ok("Material Package") => 0,
+            Ok("Source Package") => 2,
+            _ => 1,
+        });
+
+        tracks
+            .into_iter()
+            .find_map(|x| x.first_timecode().ok())
+            .or_else(|| {
+                self.video_streams
+                    .iter()
+                    .flatten()
+                    .find_map(|x| x.first_timecode().ok())
+            })
+    }
+
+    /// The video, audio and subtitle streams a player would pick for
+    /// `selector`, with the reasons for each choice.
+    pub fn select_tracks(&self, selector: &TrackSelector) -> TrackSelection {
+        selector.select(
+            self.video_streams.as_deref().unwrap_or_default(),
+            self.audio_streams.as_deref().unwrap_or_default(),
+            self.text_streams.as_deref().unwrap_or_default(),
+        )
+    }
+
+    /// Resolution class, HDR, immersive audio and the other facts derived
+    /// from the raw fields. See `MediaClass` for the rules.
+    pub fn classify(&self) -> MediaClass {
+        MediaClass::from_streams(
+            &self.general_stream,
+            self.video_streams.as_deref().unwrap_or_default(),
+            self.audio_streams.as_deref().unwrap_or_default(),
+            self.image_streams.as_deref().unwrap_or_default(),
+        )
+    }
+
+    /// Checks every rule of `policy` against the streams of its kind.
+    pub fn check_policy(&self, policy: &Policy) -> PolicyReport {
+        policy.check(|kind| match kind {
+            MediaInfoStream::General => vec![&self.general_stream as &dyn BaseStream],
+            MediaInfoStream::Video => dyn_streams(self.video_streams.as_ref()),
+            MediaInfoStream::Audio => dyn_streams(self.audio_streams.as_ref()),
+            MediaInfoStream::Text => dyn_streams(self.text_streams.as_ref()),
+            MediaInfoStream::Other => dyn_streams(self.other_streams.as_ref()),
+            MediaInfoStream::Image => dyn_streams(self.image_streams.as_ref()),
+            MediaInfoStream::Menu => dyn_streams(self.menu_streams.as_ref()),
+            MediaInfoStream::Max => Vec::new(),
+        })
+    }
+
+    /// The programs of a transport stream, one per menu stream, with their
+    /// elementary streams resolved to this wrapper's tracks.
+    pub fn programs(&self) -> Vec<Program> {
+        let resolve = |kind: MediaInfoStream, position: usize| match kind {
+            MediaInfoStream::Video => {
+                stream_at(self.video_streams.as_ref(), position).map(ProgramTrack::Video)
+            }
+            MediaInfoStream::Audio => {
+                stream_at(self.audio_streams.as_ref(), position).map(ProgramTrack::Audio)
+            }
+            MediaInfoStream::Text => {
+                stream_at(self.text_streams.as_ref(), position).map(ProgramTrack::Text)
+            }
+            MediaInfoStream::Other => {
+                stream_at(self.other_streams.as_ref(), position).map(ProgramTrack::Other)
+            }
+            MediaInfoStream::Image => {
+                stream_at(self.image_streams.as_ref(), position).map(ProgramTrack::Image)
+            }
+            _ => None,
+        };
+
+        self.menu_streams
+            .iter()
+            .flatten()
+            .filter_map(|menu| Program::from_fields(|name| menu.field(name).ok(), resolve))
+            .collect()
+    }
+
+    /// Returns an owned copy of every field of every stream
+    ///
+    /// All fields are read in a single pass while holding the handle, so the
+    /// result stays valid after `close()` and can be cloned or sent across
+    /// threads. The snapshot streams expose the same typed accessors as
+    /// `VideoStream`, `AudioStream` and the rest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the fields cannot be read from the library.
+    pub fn snapshot(&mut self) -> MediaInfoResult<MediaSnapshot> {
+        MediaSnapshot::collect(&mut self.handle.lock().unwrap())
+    }
+
+    /// Compares this file, the old side, with `other`. See `MediaDiff` for
+    /// how streams are paired.
+    pub fn diff(
+        &mut self,
+        other: &mut MediaInfoWrapper,
+        options: &DiffOptions,
+    ) -> MediaInfoResult<MediaDiff> {
+        Ok(self.snapshot()?.diff(&other.snapshot()?, options))
+    }
+
     /// Returns the full MediaInfo output as a formatted string
     ///
     /// This method returns the complete MediaInfo analysis in the default
@@ -267,13 +541,42 @@ impl MediaInfoWrapper {
     /// Returns an error if no media file has been opened, if JSON output cannot
     /// be configured, or if there are issues retrieving the information.
     pub fn get_full_inform_json(&mut self) -> MediaInfoResult<String> {
-        let _ = self.option("output", "JSON");
         // We want it at the full version here.
-        let _ = self.option("Complete", "1");
-        let result = self.inform();
-        // Reset to default Output format
-        let _ = self.option("output", "");
-        result
+        self.inform_with_options(&[("Output", "JSON"), ("Complete", "1")])
+    }
+
+    /// Returns the full MediaInfo output parsed into a typed report
+    ///
+    /// This is `get_full_inform_json()` deserialized into a
+    /// `MediaInfoReport`, with numeric and Yes/No fields converted to proper
+    /// types.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidReport` if the JSON output cannot be parsed, or any
+    /// error from `get_full_inform_json()`.
+    pub fn report(&mut self) -> MediaInfoResult<MediaInfoReport> {
+        MediaInfoReport::from_json(&self.get_full_inform_json()?)
+    }
+
+    /// Returns the MediaInfo output rendered through a custom template
+    ///
+    /// Every field referenced by the template is first checked against the
+    /// parameters the linked library knows for that stream kind. The template
+    /// is set and the previous `Inform` setting restored as in
+    /// `inform_with_options()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnknownParameter` for fields the library does not provide,
+    /// `InvalidTemplate` if the template cannot be expressed in MediaInfo's
+    /// template syntax, or any error from `inform_with_options()`.
+    pub fn inform_with_template(&mut self, template: &InformTemplate) -> MediaInfoResult<String> {
+        let catalog = self.field_catalog()?;
+        template.validate(&catalog)?;
+        let rendered = template.render()?;
+
+        self.inform_with_options(&[("Inform", &rendered)])
     }
 
     delegate! {
@@ -282,33 +585,101 @@ impl MediaInfoWrapper {
             pub fn duration(&self) -> MediaInfoResult<Duration>;
             pub fn format(&self) -> MediaInfoResult<String>;
             pub fn format_profile(&self) -> MediaInfoResult<String>;
+            pub fn format_profile_list(&self) -> MediaInfoResult<Vec<FormatProfile>>;
             pub fn format_info(&self) -> MediaInfoResult<String>;
+            #[allow(deprecated)]
             pub fn codec(&self) -> MediaInfoResult<String>;
             pub fn overall_bit_rate(&self) -> MediaInfoResult<i64>;
             pub fn writing_application(&self) -> MediaInfoResult<String>;
-            pub fn headersize(&self) -> MediaInfoResult<i64>;
-            pub fn datasize(&self) -> MediaInfoResult<i64>;
-            pub fn footersize(&self) -> MediaInfoResult<i64>;
+            pub fn header_size(&self) -> MediaInfoResult<i64>;
+            pub fn data_size(&self) -> MediaInfoResult<i64>;
+            pub fn footer_size(&self) -> MediaInfoResult<i64>;
             pub fn encoded_library(&self) -> MediaInfoResult<String>;
-            pub fn mastered_date(&self) -> MediaInfoResult<DateTime<Utc>>;
-            pub fn tagged_date(&self) -> MediaInfoResult<DateTime<Utc>>;
-            pub fn encoded_date(&self) -> MediaInfoResult<DateTime<Utc>>;
-            pub fn last_modification_date(&self) -> MediaInfoResult<DateTime<Utc>>;
-            pub fn artist(&self) -> MediaInfoResult<String>;
+            pub fn mastered_date(&self) -> MediaInfoResult<MediaDate>;
+            pub fn tagged_date(&self) -> MediaInfoResult<MediaDate>;
+            pub fn encoded_date(&self) -> MediaInfoResult<MediaDate>;
+            pub fn file_modified_date(&self) -> MediaInfoResult<MediaDate>;
             pub fn performer(&self) -> MediaInfoResult<String>;
             pub fn title(&self) -> MediaInfoResult<String>;
             pub fn genre(&self) -> MediaInfoResult<String>;
             pub fn album(&self) -> MediaInfoResult<String>;
             pub fn copyright(&self) -> MediaInfoResult<String>;
+            pub fn recorded_date(&self) -> MediaInfoResult<MediaDate>;
+            pub fn codec_id_compatible(&self) -> MediaInfoResult<Vec<String>>;
+            pub fn video_format_list(&self) -> MediaInfoResult<Vec<FormatName>>;
+            pub fn audio_format_list(&self) -> MediaInfoResult<Vec<FormatName>>;
+            pub fn text_format_list(&self) -> MediaInfoResult<Vec<FormatName>>;
+            pub fn video_language_list(&self) -> MediaInfoResult<Vec<LanguageTag>>;
+            pub fn audio_language_list(&self) -> MediaInfoResult<Vec<LanguageTag>>;
+            pub fn text_language_list(&self) -> MediaInfoResult<Vec<LanguageTag>>;
+
+            #[deprecated(note = "use `header_size`")]
+            #[call(header_size)]
+            pub fn headersize(&self) -> MediaInfoResult<i64>;
+            #[deprecated(note = "use `data_size`")]
+            #[call(data_size)]
+            pub fn datasize(&self) -> MediaInfoResult<i64>;
+            #[deprecated(note = "use `footer_size`")]
+            #[call(footer_size)]
+            pub fn footersize(&self) -> MediaInfoResult<i64>;
+            #[deprecated(note = "use `file_modified_date`")]
+            #[call(file_modified_date)]
+            pub fn last_modification_date(&self) -> MediaInfoResult<MediaDate>;
+            #[deprecated(note = "MediaInfo reports the artist as `performer`")]
+            #[call(performer)]
+            pub fn artist(&self) -> MediaInfoResult<String>;
+            #[deprecated(note = "use `recorded_date`")]
+            #[allow(deprecated)]
             pub fn year(&self) -> MediaInfoResult<String>;
         }
     }
 }
 
+fn language_matches<T: BaseStream>(stream: &T, range: &str) -> bool {
+    stream
+        .field("Language")
+        .ok()
+        .and_then(|language| LanguageTag::parse(&language))
+        .is_some_and(|tag| tag.matches(range))
+}
+
+fn with_language<'a, T: BaseStream>(streams: Option<&'a Vec<T>>, range: &str) -> Vec<&'a T> {
+    streams
+        .into_iter()
+        .flatten()
+        .filter(|stream| language_matches(*stream, range))
+        .collect()
+}
+
+fn preferred<'a, T: BaseStream>(
+    streams: Option<&'a Vec<T>>,
+    preferences: &[&str],
+) -> Option<&'a T> {
+    let streams = streams?;
+    preferences.iter().find_map(|range| {
+        streams
+            .iter()
+            .find(|stream| language_matches(*stream, range))
+    })
+}
+
+fn dyn_streams<T: BaseStream>(streams: Option<&Vec<T>>) -> Vec<&dyn BaseStream> {
+    streams
+        .into_iter()
+        .flatten()
+        .map(|x| x as &dyn BaseStream)
+        .collect()
+}
+
+fn stream_at<T: Clone>(streams: Option<&Vec<T>>, position: usize) -> Option<T> {
+    streams?.get(position).cloned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::NaiveDate;
+    use crate::inform_template::Section;
+    use chrono::{TimeZone, Utc};
     use std::fs;
     use std::path::PathBuf;
 
@@ -330,17 +701,12 @@ mod tests {
             "HandBrake 0.9.4 2009112300",
             mw.writing_application().unwrap()
         );
-        assert_eq!(160, mw.headersize().unwrap());
-        assert_eq!(379880, mw.datasize().unwrap());
-        assert_eq!(3591, mw.footersize().unwrap());
+        assert_eq!(160, mw.header_size().unwrap());
+        assert_eq!(379880, mw.data_size().unwrap());
+        assert_eq!(3591, mw.footer_size().unwrap());
         assert_eq!(
-            DateTime::<Utc>::from_naive_utc_and_offset(
-                NaiveDate::from_ymd_opt(2010, 3, 20)
-                    .and_then(|d| d.and_hms_opt(21, 29, 12))
-                    .unwrap(),
-                Utc
-            ),
-            mw.tagged_date().unwrap()
+            Utc.with_ymd_and_hms(2010, 3, 20, 21, 29, 12).single(),
+            mw.tagged_date().unwrap().to_utc()
         );
         mw.close();
     }
@@ -396,6 +762,62 @@ mod tests {
         mw.close();
     }
 
+    #[test]
+    fn can_parse_report() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("sample.mp4");
+        let mut mw = MediaInfoWrapper::new();
+        mw.open(filename.as_path()).unwrap();
+
+        let report = mw.report().expect("Should parse the JSON output");
+        assert!(report.general().is_some());
+        assert_eq!(report.video().count(), 1);
+        assert_eq!(report.audio().count(), 1);
+
+        mw.close();
+    }
+
+    #[test]
+    fn can_take_snapshot() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("sample.mp4");
+        let mut mw = MediaInfoWrapper::new();
+        mw.open(filename.as_path()).unwrap();
+
+        let snapshot = mw.snapshot().expect("Should collect every stream");
+        mw.close();
+
+        assert_eq!(snapshot.video.len(), 1);
+        assert_eq!(snapshot.audio.len(), 1);
+        assert!(snapshot.video[0].width().unwrap() > 0);
+        assert!(snapshot.general.format().is_ok());
+    }
+
+    #[test]
+    fn can_render_inform_template() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("sample.mp4");
+        let mut mw = MediaInfoWrapper::new();
+        mw.open(filename.as_path()).unwrap();
+
+        let template = InformTemplate::new().section(
+            MediaInfoStream::Video,
+            Section::new().field("Width").text("x").field("Height"),
+        );
+        let previous = mw.option("Inform_Get", "").unwrap_or_default();
+
+        let output = mw
+            .inform_with_template(&template)
+            .expect("Should render the template");
+        assert!(output.contains("x"));
+        assert!(!output.contains("%Width%"));
+
+        // The previous output setting is restored.
+        assert_eq!(mw.option("Inform_Get", "").unwrap_or_default(), previous);
+
+        mw.close();
+    }
+
     #[test]
     fn can_get_full_inform_text() {
         let sample_path = PathBuf::from("samples");
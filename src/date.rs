@@ -0,0 +1,192 @@
+// Dates as MediaInfo reports them.
+//
+// Depending on the container and on the tag, a date field may hold a full
+// timestamp with a zone ("2010-03-20 21:29:12 UTC", "UTC 2010-03-20
+// 21:29:12", ISO 8601 with an offset), a local time without a zone
+// ("2010-03-20 22:29:12", `File_Modified_Date_Local`), a calendar date or
+// only a year ("2010", common in `Recorded_Date`). `MediaDate` keeps what
+// was actually stored instead of inventing the missing parts.
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MediaDate {
+    /// A timestamp with a known UTC offset.
+    DateTime(DateTime<FixedOffset>),
+    /// A timestamp without a zone, e.g. a local file time.
+    Local(NaiveDateTime),
+    Date(NaiveDate),
+    YearMonth {
+        year: i32,
+        month: u32,
+    },
+    Year(i32),
+}
+
+impl MediaDate {
+    /// Parses any of the date renderings MediaInfo produces. When several
+    /// values are joined with " / " the first one is used.
+    pub fn parse(value: &str) -> Option<MediaDate> {
+        let value = value.split(" / ").next()?.trim();
+        if value.is_empty() {
+            return None;
+        }
+
+        // "UTC 2010-03-20 21:29:12" and "2010-03-20 21:29:12 UTC"
+        let utc = value
+            .strip_prefix("UTC ")
+            .or_else(|| value.strip_suffix(" UTC"))
+            .or_else(|| value.strip_suffix('Z'));
+        if let Some(rest) = utc {
+            return parse_naive_datetime(rest.trim())
+                .map(|naive| MediaDate::DateTime(Utc.from_utc_datetime(&naive).fixed_offset()));
+        }
+
+        if let Some(date_time) = parse_offset_datetime(value) {
+            return Some(MediaDate::DateTime(date_time));
+        }
+        if let Some(naive) = parse_naive_datetime(value) {
+            return Some(MediaDate::Local(naive));
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            return Some(MediaDate::Date(date));
+        }
+        if let Some((year, month)) = value.split_once('-')
+            && year.len() == 4
+            && month.len() == 2
+            && let (Ok(year), Ok(month)) = (year.parse(), month.parse())
+            && (1..=12).contains(&month)
+        {
+            return Some(MediaDate::YearMonth { year, month });
+        }
+        if value.len() == 4 && value.chars().all(|c| c.is_ascii_digit()) {
+            return value.parse().ok().map(MediaDate::Year);
+        }
+
+        None
+    }
+
+    pub fn year(&self) -> i32 {
+        match self {
+            MediaDate::DateTime(x) => x.year(),
+            MediaDate::Local(x) => x.year(),
+            MediaDate::Date(x) => x.year(),
+            MediaDate::YearMonth { year, .. } => *year,
+            MediaDate::Year(year) => *year,
+        }
+    }
+
+    /// The calendar date, when the value has a day.
+    pub fn date(&self) -> Option<NaiveDate> {
+        match self {
+            MediaDate::DateTime(x) => Some(x.date_naive()),
+            MediaDate::Local(x) => Some(x.date()),
+            MediaDate::Date(x) => Some(*x),
+            MediaDate::YearMonth { .. } | MediaDate::Year(_) => None,
+        }
+    }
+
+    /// The instant in UTC, only available when the zone is known.
+    pub fn to_utc(self) -> Option<DateTime<Utc>> {
+        match self {
+            MediaDate::DateTime(x) => Some(x.with_timezone(&Utc)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for MediaDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaDate::DateTime(x) => write!(f, "{}", x.to_rfc3339()),
+            MediaDate::Local(x) => write!(f, "{}", x.format("%Y-%m-%dT%H:%M:%S%.f")),
+            MediaDate::Date(x) => write!(f, "{}", x.format("%Y-%m-%d")),
+            MediaDate::YearMonth { year, month } => write!(f, "{year:04}-{month:02}"),
+            MediaDate::Year(year) => write!(f, "{year:04}"),
+        }
+    }
+}
+
+// "2010-03-20 21:29:12", "2010-03-20T21:29:12.345"
+fn parse_naive_datetime(value: &str) -> Option<NaiveDateTime> {
+    let (date, time) = value.split_once([' ', 'T'])?;
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    let time = NaiveTime::parse_from_str(time, "%H:%M:%S%.f")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+        .ok()?;
+    Some(date.and_time(time))
+}
+
+// "2010-03-20T21:29:12+02:00", "2010-03-20 21:29:12.5 -0500"
+fn parse_offset_datetime(value: &str) -> Option<DateTime<FixedOffset>> {
+    [
+        "%Y-%m-%dT%H:%M:%S%.f%:z",
+        "%Y-%m-%d %H:%M:%S%.f%:z",
+        "%Y-%m-%dT%H:%M:%S%.f%z",
+        "%Y-%m-%d %H:%M:%S%.f %z",
+    ]
+    .iter()
+    .find_map(|format| DateTime::parse_from_str(value, format).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, s).unwrap()
+    }
+
+    #[test]
+    fn parses_utc_timestamps() {
+        let expected = Some(utc(2010, 3, 20, 21, 29, 12));
+
+        for value in [
+            "2010-03-20 21:29:12 UTC",
+            "UTC 2010-03-20 21:29:12",
+            "2010-03-20T21:29:12Z",
+            "2010-03-20T23:29:12+02:00",
+            "UTC 2010-03-20 21:29:12 / UTC 2010-03-20 21:29:13",
+        ] {
+            assert_eq!(MediaDate::parse(value).unwrap().to_utc(), expected);
+        }
+
+        let precise = MediaDate::parse("2010-03-20 21:29:12.250 UTC").unwrap();
+        assert_eq!(precise.to_string(), "2010-03-20T21:29:12.250+00:00");
+    }
+
+    #[test]
+    fn keeps_offsets_and_local_times() {
+        let offset = MediaDate::parse("2010-03-20T16:29:12-05:00").unwrap();
+        assert_eq!(offset.to_string(), "2010-03-20T16:29:12-05:00");
+
+        let local = MediaDate::parse("2010-03-20 22:29:12").unwrap();
+        assert!(matches!(local, MediaDate::Local(_)));
+        assert_eq!(local.to_utc(), None);
+        assert_eq!(local.date(), NaiveDate::from_ymd_opt(2010, 3, 20));
+    }
+
+    #[test]
+    fn parses_partial_dates() {
+        assert_eq!(
+            MediaDate::parse("2010-03-20"),
+            NaiveDate::from_ymd_opt(2010, 3, 20).map(MediaDate::Date)
+        );
+        assert_eq!(
+            MediaDate::parse("2010-03"),
+            Some(MediaDate::YearMonth {
+                year: 2010,
+                month: 3
+            })
+        );
+
+        let year = MediaDate::parse("1999").unwrap();
+        assert_eq!(year, MediaDate::Year(1999));
+        assert_eq!(year.year(), 1999);
+        assert_eq!(year.date(), None);
+
+        assert_eq!(MediaDate::parse("last summer"), None);
+        assert_eq!(MediaDate::parse(""), None);
+    }
+}
@@ -0,0 +1,495 @@
+// Field-by-field differences between two snapshots.
+//
+// Streams are compared kind by kind. They are paired by `ID` when every
+// stream of that kind has a distinct one on both sides, so a remux that
+// reorders tracks is not reported as a change of every track, and by index
+// otherwise.
+
+use crate::ffi::{MediaInfoError, MediaInfoResult, MediaInfoStream};
+use crate::snapshot::MediaSnapshot;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StreamAlignment {
+    /// By `ID` when it identifies every stream, by index otherwise.
+    #[default]
+    Auto,
+    Index,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffOptions {
+    /// Field names to leave out. A `*` matches any run of characters, e.g.
+    /// "*_Date" or "StreamSize*".
+    pub ignore: Vec<String>,
+    pub alignment: StreamAlignment,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiffStatus {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FieldDiff {
+    pub field: String,
+    pub status: DiffStatus,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StreamDiff {
+    pub kind: MediaInfoStream,
+    pub old_index: Option<usize>,
+    pub new_index: Option<usize>,
+    pub id: Option<String>,
+    pub status: DiffStatus,
+    /// For added and removed streams, every field of the stream.
+    pub fields: Vec<FieldDiff>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MediaDiff {
+    /// Only the streams that differ.
+    pub streams: Vec<StreamDiff>,
+}
+
+// Fields that change with every run or copy of a file rather than with its
+// content.
+const VOLATILE_FIELDS: &[&str] = &[
+    "CompleteName*",
+    "FolderName",
+    "FileName*",
+    "FileExtension*",
+    "File_Created_Date*",
+    "File_Modified_Date*",
+    "*_Date",
+    "*_Date_*",
+    "FileSize*",
+    "StreamSize*",
+];
+
+impl DiffOptions {
+    /// Ignores dates, file names and sizes.
+    pub fn ignoring_volatile() -> DiffOptions {
+        DiffOptions {
+            ignore: VOLATILE_FIELDS.iter().map(|x| x.to_string()).collect(),
+            ..DiffOptions::default()
+        }
+    }
+
+    fn ignores(&self, field: &str) -> bool {
+        self.ignore
+            .iter()
+            .any(|pattern| wildcard_match(pattern, field))
+    }
+}
+
+impl MediaDiff {
+    pub fn between(old: &MediaSnapshot, new: &MediaSnapshot, options: &DiffOptions) -> MediaDiff {
+        let kinds = [
+            (
+                MediaInfoStream::General,
+                vec![&old.general.fields],
+                vec![&new.general.fields],
+            ),
+            (
+                MediaInfoStream::Video,
+                fields(&old.video, |x| &x.fields),
+                fields(&new.video, |x| &x.fields),
+            ),
+            (
+                MediaInfoStream::Audio,
+                fields(&old.audio, |x| &x.fields),
+                fields(&new.audio, |x| &x.fields),
+            ),
+            (
+                MediaInfoStream::Text,
+                fields(&old.text, |x| &x.fields),
+                fields(&new.text, |x| &x.fields),
+            ),
+            (
+                MediaInfoStream::Other,
+                fields(&old.other, |x| &x.fields),
+                fields(&new.other, |x| &x.fields),
+            ),
+            (
+                MediaInfoStream::Image,
+                fields(&old.image, |x| &x.fields),
+                fields(&new.image, |x| &x.fields),
+            ),
+            (
+                MediaInfoStream::Menu,
+                fields(&old.menu, |x| &x.fields),
+                fields(&new.menu, |x| &x.fields),
+            ),
+        ];
+
+        let mut streams = Vec::new();
+        for (kind, old, new) in kinds {
+            for (old_index, new_index) in align(&old, &new, options.alignment) {
+                let before = old_index.map(|i| old[i]);
+                let after = new_index.map(|i| new[i]);
+                let id = before.or(after).and_then(|x| x.get("ID")).cloned();
+
+                let (status, fields) = match (before, after) {
+                    (Some(before), Some(after)) => {
+                        let fields = diff_fields(before, after, options);
+                        if fields.is_empty() {
+                            continue;
+                        }
+                        (DiffStatus::Changed, fields)
+                    }
+                    (Some(before), None) => (
+                        DiffStatus::Removed,
+                        whole(before, DiffStatus::Removed, options),
+                    ),
+                    (None, Some(after)) => {
+                        (DiffStatus::Added, whole(after, DiffStatus::Added, options))
+                    }
+                    (None, None) => continue,
+                };
+
+                streams.push(StreamDiff {
+                    kind,
+                    old_index,
+                    new_index,
+                    id,
+                    status,
+                    fields,
+                });
+            }
+        }
+
+        MediaDiff { streams }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+
+    pub fn to_json(&self) -> MediaInfoResult<String> {
+        serde_json::to_string(self).map_err(|e| MediaInfoError::InvalidReport(e.to_string()))
+    }
+}
+
+fn fields<T>(
+    streams: &[T],
+    fields: impl Fn(&T) -> &BTreeMap<String, String>,
+) -> Vec<&BTreeMap<String, String>> {
+    streams.iter().map(fields).collect()
+}
+
+// Pairs of (old position, new position), in the order of the new file with
+// the removed streams last.
+fn align(
+    old: &[&BTreeMap<String, String>],
+    new: &[&BTreeMap<String, String>],
+    alignment: StreamAlignment,
+) -> Vec<(Option<usize>, Option<usize>)> {
+    let by_id = match alignment {
+        StreamAlignment::Index => None,
+        StreamAlignment::Auto => distinct_ids(old).zip(distinct_ids(new)),
+    };
+    let Some((old_ids, new_ids)) = by_id else {
+        return (0..old.len().max(new.len()))
+            .map(|i| ((i < old.len()).then_some(i), (i < new.len()).then_some(i)))
+            .collect();
+    };
+
+    let mut pairs: Vec<(Option<usize>, Option<usize>)> = new_ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (old_ids.iter().position(|x| x == id), Some(i)))
+        .collect();
+    pairs.extend(
+        old_ids
+            .iter()
+            .enumerate()
+            .filter(|(_, id)| !new_ids.contains(id))
+            .map(|(i, _)| (Some(i), None)),
+    );
+    pairs
+}
+
+// The `ID` of every stream, when each has one and no two share it.
+fn distinct_ids<'a>(streams: &[&'a BTreeMap<String, String>]) -> Option<Vec<&'a String>> {
+    let ids: Vec<&String> = streams.iter().map(|x| x.get("ID")).collect::<Option<_>>()?;
+    let distinct: BTreeSet<&String> = ids.iter().copied().collect();
+    (distinct.len() == ids.len()).then_some(ids)
+}
+
+fn diff_fields(
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+    options: &DiffOptions,
+) -> Vec<FieldDiff> {
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    names
+        .into_iter()
+        .filter(|name| !options.ignores(name))
+        .filter_map(|name| {
+            let before = old.get(name).filter(|x| !x.is_empty());
+            let after = new.get(name).filter(|x| !x.is_empty());
+            let status = match (before, after) {
+                (Some(a), Some(b)) if a == b => return None,
+                (Some(_), Some(_)) => DiffStatus::Changed,
+                (Some(_), None) => DiffStatus::Removed,
+                (None, Some(_)) => DiffStatus::Added,
+                (None, None) => return None,
+            };
+            Some(FieldDiff {
+                field: name.clone(),
+                status,
+                old: before.cloned(),
+                new: after.cloned(),
+            })
+        })
+        .collect()
+}
+
+fn whole(
+    fields: &BTreeMap<String, String>,
+    status: DiffStatus,
+    options: &DiffOptions,
+) -> Vec<FieldDiff> {
+    fields
+        .iter()
+        .filter(|(name, value)| !value.is_empty() && !options.ignores(name))
+        .map(|(name, value)| FieldDiff {
+            field: name.clone(),
+            status,
+            old: (status == DiffStatus::Removed).then(|| value.clone()),
+            new: (status == DiffStatus::Added).then(|| value.clone()),
+        })
+        .collect()
+}
+
+// "*" matches any run of characters, everything else matches itself.
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+    if !pattern.contains('*') {
+        return rest.is_empty();
+    }
+
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+impl fmt::Display for DiffStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DiffStatus::Added => "added",
+            DiffStatus::Removed => "removed",
+            DiffStatus::Changed => "changed",
+        })
+    }
+}
+
+impl fmt::Display for MediaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.streams.is_empty() {
+            return writeln!(f, "No differences");
+        }
+
+        for stream in &self.streams {
+            write!(f, "{}", stream.kind.name())?;
+            if let Some(index) = stream.new_index.or(stream.old_index) {
+                write!(f, " #{index}")?;
+            }
+            if let Some(id) = &stream.id {
+                write!(f, " (ID {id})")?;
+            }
+            writeln!(f, ": {}", stream.status)?;
+
+            for field in &stream.fields {
+                let old = field.old.as_deref().unwrap_or_default();
+                let new = field.new.as_deref().unwrap_or_default();
+                match field.status {
+                    DiffStatus::Added => writeln!(f, "  + {}: {new}", field.field)?,
+                    DiffStatus::Removed => writeln!(f, "  - {}: {old}", field.field)?,
+                    DiffStatus::Changed => writeln!(f, "  ~ {}: {old} -> {new}", field.field)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::{AudioSnapshot, GeneralSnapshot, VideoSnapshot};
+
+    fn media(general: &[(&str, &str)], audio: Vec<AudioSnapshot>) -> MediaSnapshot {
+        MediaSnapshot {
+            general: GeneralSnapshot::from_pairs(0, general),
+            video: vec![VideoSnapshot::from_pairs(
+                0,
+                &[("ID", "1"), ("Format", "AVC")],
+            )],
+            audio,
+            text: Vec::new(),
+            other: Vec::new(),
+            image: Vec::new(),
+            menu: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn pairs_reordered_streams_by_id() {
+        let old = media(
+            &[("FileSize", "1000"), ("Title", "Movie")],
+            vec![
+                AudioSnapshot::from_pairs(
+                    0,
+                    &[("ID", "2"), ("Language", "en"), ("Format", "AC-3")],
+                ),
+                AudioSnapshot::from_pairs(
+                    1,
+                    &[("ID", "3"), ("Language", "fr"), ("Format", "AC-3")],
+                ),
+                AudioSnapshot::from_pairs(2, &[("ID", "4"), ("Language", "de")]),
+            ],
+        );
+        let new = media(
+            &[("FileSize", "1200"), ("Title", "Movie (2024)")],
+            vec![
+                AudioSnapshot::from_pairs(
+                    0,
+                    &[("ID", "3"), ("Language", "fr"), ("Format", "E-AC-3")],
+                ),
+                AudioSnapshot::from_pairs(
+                    1,
+                    &[("ID", "2"), ("Language", "en"), ("Format", "AC-3")],
+                ),
+                AudioSnapshot::from_pairs(2, &[("ID", "5"), ("Language", "es")]),
+            ],
+        );
+
+        let diff = old.diff(&new, &DiffOptions::ignoring_volatile());
+        let summary: Vec<_> = diff
+            .streams
+            .iter()
+            .map(|x| (x.kind, x.id.as_deref(), x.status))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (MediaInfoStream::General, None, DiffStatus::Changed),
+                (MediaInfoStream::Audio, Some("3"), DiffStatus::Changed),
+                (MediaInfoStream::Audio, Some("5"), DiffStatus::Added),
+                (MediaInfoStream::Audio, Some("4"), DiffStatus::Removed),
+            ]
+        );
+        assert_eq!(
+            diff.streams[0].fields,
+            [FieldDiff {
+                field: "Title".to_string(),
+                status: DiffStatus::Changed,
+                old: Some("Movie".to_string()),
+                new: Some("Movie (2024)".to_string()),
+            }]
+        );
+        assert_eq!(
+            (diff.streams[1].old_index, diff.streams[1].new_index),
+            (Some(1), Some(0))
+        );
+
+        let by_index = old.diff(
+            &new,
+            &DiffOptions {
+                alignment: StreamAlignment::Index,
+                ..DiffOptions::ignoring_volatile()
+            },
+        );
+        assert!(
+            by_index
+                .streams
+                .iter()
+                .filter(|x| x.kind == MediaInfoStream::Audio)
+                .all(|x| x.status == DiffStatus::Changed)
+        );
+    }
+
+    #[test]
+    fn reports_bit_rate_changes_as_content() {
+        let old = media(
+            &[("FileSize", "1000"), ("OverallBitRate", "8000")],
+            Vec::new(),
+        );
+        let new = media(
+            &[("FileSize", "2000"), ("OverallBitRate", "16000")],
+            Vec::new(),
+        );
+
+        let diff = old.diff(&new, &DiffOptions::ignoring_volatile());
+        let fields: Vec<&str> = diff.streams[0]
+            .fields
+            .iter()
+            .map(|x| x.field.as_str())
+            .collect();
+        assert_eq!(fields, ["OverallBitRate"]);
+    }
+
+    #[test]
+    fn renders_text_and_json() {
+        let old = media(
+            &[("Encoded_Date", "2020-01-01"), ("Format", "MPEG-4")],
+            vec![AudioSnapshot::from_pairs(0, &[("Channels", "2")])],
+        );
+        let new = media(
+            &[("Encoded_Date", "2024-05-05"), ("Format", "MPEG-4")],
+            vec![AudioSnapshot::from_pairs(
+                0,
+                &[("Channels", "6"), ("ChannelLayout", "L R C LFE Ls Rs")],
+            )],
+        );
+
+        let diff = old.diff(&new, &DiffOptions::ignoring_volatile());
+        assert_eq!(
+            diff.to_string(),
+            "Audio #0: changed\n  + ChannelLayout: L R C LFE Ls Rs\n  ~ Channels: 2 -> 6\n"
+        );
+        let json: serde_json::Value = serde_json::from_str(&diff.to_json().unwrap()).unwrap();
+        assert_eq!(json["streams"][0]["fields"][1]["new"], "6");
+
+        let everything = old.diff(&new, &DiffOptions::default());
+        assert!(
+            everything
+                .to_string()
+                .starts_with("General #0: changed\n  ~ Encoded_Date")
+        );
+        assert!(old.diff(&old, &DiffOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn wildcards_match_field_names() {
+        assert!(wildcard_match("*_Date", "Encoded_Date"));
+        assert!(wildcard_match("StreamSize*", "StreamSize_String"));
+        assert!(wildcard_match("*Size*", "FileSize"));
+        assert!(wildcard_match("Format", "Format"));
+        assert!(!wildcard_match("Format", "Format_Profile"));
+        assert!(!wildcard_match("*_Date", "Date_Encoded"));
+    }
+}
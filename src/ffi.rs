@@ -1,5 +1,11 @@
 use crate::c_w_string::CWcharString;
+use crate::capabilities::LibraryVersion;
+use crate::field_catalog::FieldCatalog;
+use crate::parsing;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
 use std::path::Path;
+use std::sync::Mutex;
 
 type Uint64 = u64;
 type Uint8 = u8;
@@ -10,7 +16,7 @@ type Void = libc::c_void;
 type CMediaInfoStream = std::ffi::c_int;
 type CMediaInfoInfo = std::ffi::c_int;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum MediaInfoStream {
     General = 0,
     Video,
@@ -39,6 +45,32 @@ impl MediaInfoStream {
             MediaInfoStream::Max,
         ]
     }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MediaInfoStream::General => "General",
+            MediaInfoStream::Video => "Video",
+            MediaInfoStream::Audio => "Audio",
+            MediaInfoStream::Text => "Text",
+            MediaInfoStream::Other => "Other",
+            MediaInfoStream::Image => "Image",
+            MediaInfoStream::Menu => "Menu",
+            MediaInfoStream::Max => "Max",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<MediaInfoStream> {
+        match name {
+            "General" => Some(MediaInfoStream::General),
+            "Video" => Some(MediaInfoStream::Video),
+            "Audio" => Some(MediaInfoStream::Audio),
+            "Text" => Some(MediaInfoStream::Text),
+            "Other" => Some(MediaInfoStream::Other),
+            "Image" => Some(MediaInfoStream::Image),
+            "Menu" => Some(MediaInfoStream::Menu),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -65,6 +97,12 @@ pub struct MediaInfo {
     handle: *mut Void,
 }
 
+// libmediainfo keeps Output/Inform, Complete, ParseSpeed and most other
+// options in one configuration shared by every handle in the process. Calls
+// that change one of them for their own use hold this lock from setting it
+// until it is restored, so they never see each other's values.
+static GLOBAL_OPTIONS: Mutex<()> = Mutex::new(());
+
 impl Default for MediaInfo {
     fn default() -> Self {
         unsafe {
@@ -164,6 +202,77 @@ impl MediaInfo {
         }
     }
 
+    /// Runs `f` with `options` set, then restores their previous values,
+    /// read back through the matching `<option>_Get`.
+    ///
+    /// Those options are process-wide: other calls through here wait until
+    /// they are restored, but handles calling `option()` directly meanwhile
+    /// see them too.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnknownParameter` for options the library cannot read back,
+    /// otherwise the error of `f` or, failing that, of the restore.
+    pub fn with_options<T>(
+        &mut self,
+        options: &[(&str, &str)],
+        f: impl FnOnce(&mut MediaInfo) -> MediaInfoResult<T>,
+    ) -> MediaInfoResult<T> {
+        if options.is_empty() {
+            return f(self);
+        }
+        let _guard = GLOBAL_OPTIONS.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut previous = Vec::with_capacity(options.len());
+        let mut result = Ok(());
+        for (name, value) in options {
+            result = self.get_option(name).and_then(|current| {
+                previous.push((*name, current));
+                self.set_option(name, value)
+            });
+            if result.is_err() {
+                break;
+            }
+        }
+        let result = result.and_then(|_| f(self));
+
+        let mut restored = Ok(());
+        for (name, value) in previous.iter().rev() {
+            restored = restored.and(self.set_option(name, value));
+        }
+        let value = result?;
+        restored.map(|_| value)
+    }
+
+    fn get_option(&mut self, name: &str) -> MediaInfoResult<String> {
+        match self.option(&format!("{name}_Get"), "") {
+            Ok(value) if value == "Option not known" => {
+                Err(MediaInfoError::UnknownParameter(name.to_string()))
+            }
+            Ok(value) => Ok(value),
+            Err(MediaInfoError::ZeroLengthResult) => Ok(String::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn set_option(&mut self, name: &str, value: &str) -> MediaInfoResult<()> {
+        // Setters answer with an empty string, or a message when they fail.
+        match self.option(name, value) {
+            Ok(message) if message == "Option not known" => {
+                Err(MediaInfoError::UnknownParameter(name.to_string()))
+            }
+            Ok(_) | Err(MediaInfoError::ZeroLengthResult) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The separator placed between the values of a field filled more than
+    /// once, as set with the `TagSeparator` option.
+    pub fn tag_separator(&mut self) -> String {
+        self.option("TagSeparator_Get", "")
+            .unwrap_or_else(|_| parsing::DEFAULT_SEPARATOR.to_string())
+    }
+
     pub fn inform(&mut self) -> MediaInfoResult<String> {
         unsafe {
             #[cfg(not(target_arch = "wasm32"))]
@@ -254,10 +363,123 @@ impl MediaInfo {
         }
     }
 
+    /// Every non-empty field of one stream as `(name, value)` pairs, in the
+    /// order the library reports them.
+    pub fn stream_fields(
+        &mut self,
+        stream_kind: MediaInfoStream,
+        stream_number: usize,
+    ) -> MediaInfoResult<Vec<(String, String)>> {
+        let mut fields = Vec::new();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let count = unsafe {
+                MediaInfo_Count_Get(self.handle, stream_kind.c_compatible(), stream_number)
+            };
+            for parameter in 0..count {
+                let name = self.get_i(stream_kind, stream_number, parameter, MediaInfoInfo::Name);
+                let value = self.get_i(stream_kind, stream_number, parameter, MediaInfoInfo::Text);
+                if let (Ok(name), Ok(value)) = (name, value) {
+                    fields.push((name, value));
+                }
+            }
+        }
+
+        // The JS bridge only exposes lookups by name, so walk the catalog.
+        #[cfg(target_arch = "wasm32")]
+        {
+            let catalog = self.field_catalog()?;
+            for info in catalog.fields(stream_kind) {
+                let value = self.get(
+                    stream_kind,
+                    stream_number,
+                    &info.name,
+                    MediaInfoInfo::Text,
+                    MediaInfoInfo::Name,
+                )?;
+                if !value.is_empty() {
+                    fields.push((info.name.clone(), value));
+                }
+            }
+        }
+
+        Ok(fields)
+    }
+
+    /// The `(name, value)` pairs at parameter positions `positions` of one
+    /// stream, e.g. the chapters between `Chapters_Pos_Begin` and
+    /// `Chapters_Pos_End` of a menu. Empty fields are skipped.
+    pub fn fields_at(
+        &mut self,
+        stream_kind: MediaInfoStream,
+        stream_number: usize,
+        positions: Range<usize>,
+    ) -> MediaInfoResult<Vec<(String, String)>> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut fields = Vec::new();
+            for parameter in positions {
+                let name = self.get_i(stream_kind, stream_number, parameter, MediaInfoInfo::Name);
+                let value = self.get_i(stream_kind, stream_number, parameter, MediaInfoInfo::Text);
+                if let (Ok(name), Ok(value)) = (name, value) {
+                    fields.push((name, value));
+                }
+            }
+            Ok(fields)
+        }
+
+        // The JS bridge only exposes lookups by name.
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = (stream_kind, stream_number, positions);
+            Ok(Vec::new())
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn get_i(
+        &mut self,
+        info_stream: MediaInfoStream,
+        stream_number: usize,
+        parameter: usize,
+        info_kind: MediaInfoInfo,
+    ) -> MediaInfoResult<String> {
+        unsafe {
+            let result_ptr = MediaInfo_GetI(
+                self.handle,
+                info_stream.c_compatible(),
+                stream_number as SizeT,
+                parameter as SizeT,
+                info_kind.c_compatible(),
+            );
+
+            let result = CWcharString::from_raw_to_string(result_ptr)
+                .map_err(|_| MediaInfoError::CToRust)?;
+
+            if result.is_empty() {
+                return Err(MediaInfoError::ZeroLengthResult);
+            }
+
+            Ok(result)
+        }
+    }
+
     pub fn available_parameters(&mut self) -> MediaInfoResult<String> {
         self.option("Info_Parameters", "")
     }
 
+    pub fn available_parameters_csv(&mut self, complete: bool) -> MediaInfoResult<String> {
+        self.option(
+            "Info_Parameters_CSV",
+            if complete { "Complete" } else { "" },
+        )
+    }
+
+    pub fn field_catalog(&mut self) -> MediaInfoResult<FieldCatalog> {
+        Ok(FieldCatalog::parse(&self.available_parameters_csv(true)?))
+    }
+
     pub fn open_buffer_init(&mut self, buffer_size: u64, offset: u64) -> usize {
         unsafe {
             #[cfg(not(target_arch = "wasm32"))]
@@ -362,6 +584,18 @@ pub enum MediaInfoError {
     ZeroLengthResult,
     NonNumericResult,
     NoDataOpen,
+    UnknownParameter(String),
+    InvalidTemplate(String),
+    InvalidReport(String),
+    InvalidDate(String),
+    InvalidLanguage(String),
+    InvalidTimecode(String),
+    InvalidPolicy(String),
+    Io(String),
+    VersionMismatch {
+        linked: LibraryVersion,
+        vendored: LibraryVersion,
+    },
 }
 
 pub type MediaInfoResult<T> = Result<T, MediaInfoError>;
@@ -414,6 +648,14 @@ unsafe extern "C" {
         info_kind: CMediaInfoInfo,
         search_kind: CMediaInfoInfo,
     ) -> *const Wchar;
+
+    fn MediaInfo_GetI(
+        handle: *mut Void,
+        info_stream: CMediaInfoStream,
+        stream_number: SizeT,
+        parameter: SizeT,
+        info_kind: CMediaInfoInfo,
+    ) -> *const Wchar;
 }
 
 #[cfg(target_arch = "wasm32")]
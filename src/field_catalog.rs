@@ -0,0 +1,195 @@
+// Catalog of every field libmediainfo can report, parsed from the
+// `Info_Parameters_CSV` option.
+//
+// With the "Complete" value each line carries the full parameter row:
+// `Name;Text;Measure;Options;Name_Text;Measure_Text;Info;HowTo`. Stream
+// kinds are introduced by a heading line and separated by a blank line.
+
+use crate::ffi::MediaInfoStream;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FieldType {
+    Text,
+    Integer,
+    Float,
+    Other(char),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldInfo {
+    pub stream_kind: MediaInfoStream,
+    pub name: String,
+    /// Unit appended to the value in human readable output, e.g. "pixel".
+    pub measure: String,
+    /// Empty when the library was built without parameter descriptions.
+    pub description: String,
+    pub field_type: FieldType,
+    pub show_in_inform: bool,
+    pub is_string_variant: bool,
+}
+
+impl FieldInfo {
+    /// The field this one is a `/String` rendering of, e.g. `Duration`
+    /// for `Duration/String3`.
+    pub fn base_name(&self) -> &str {
+        match self.name.find("/String") {
+            Some(pos) => &self.name[..pos],
+            None => &self.name,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FieldCatalog {
+    fields: Vec<FieldInfo>,
+}
+
+impl FieldCatalog {
+    pub fn parse(info_parameters_csv: &str) -> FieldCatalog {
+        let mut fields = Vec::new();
+        let mut current: Option<Option<MediaInfoStream>> = None;
+
+        for line in info_parameters_csv.lines() {
+            let line = line.trim_end_matches('\r');
+            if line.is_empty() {
+                current = None;
+                continue;
+            }
+
+            let columns = split_row(line, ';');
+            let kind = match current {
+                None => {
+                    current = Some(MediaInfoStream::from_name(&columns[0]));
+                    continue;
+                }
+                Some(None) => continue,
+                Some(Some(kind)) => kind,
+            };
+
+            let column = |i: usize| columns.get(i).map(|s| s.as_str()).unwrap_or_default();
+            let options: Vec<char> = column(3).chars().collect();
+            let name = column(0).to_string();
+
+            fields.push(FieldInfo {
+                stream_kind: kind,
+                is_string_variant: name.contains("/String"),
+                measure: column(2).trim().to_string(),
+                description: column(6).to_string(),
+                field_type: match options.get(3) {
+                    Some('T') => FieldType::Text,
+                    Some('I') => FieldType::Integer,
+                    Some('F') => FieldType::Float,
+                    Some(c) => FieldType::Other(*c),
+                    None => FieldType::Text,
+                },
+                show_in_inform: options.first() == Some(&'Y'),
+                name,
+            });
+        }
+
+        FieldCatalog { fields }
+    }
+
+    pub fn all(&self) -> &[FieldInfo] {
+        &self.fields
+    }
+
+    pub fn fields(&self, kind: MediaInfoStream) -> impl Iterator<Item = &FieldInfo> {
+        self.fields.iter().filter(move |f| f.stream_kind == kind)
+    }
+
+    pub fn get(&self, kind: MediaInfoStream, name: &str) -> Option<&FieldInfo> {
+        self.fields(kind).find(|f| f.name == name)
+    }
+
+    pub fn contains(&self, kind: MediaInfoStream, name: &str) -> bool {
+        self.get(kind, name).is_some()
+    }
+
+    /// Looks a field name up in every stream kind.
+    pub fn find(&self, name: &str) -> Vec<&FieldInfo> {
+        self.fields.iter().filter(|f| f.name == name).collect()
+    }
+
+    pub fn with_prefix(&self, kind: MediaInfoStream, prefix: &str) -> Vec<&FieldInfo> {
+        self.fields(kind)
+            .filter(|f| f.name.starts_with(prefix))
+            .collect()
+    }
+}
+
+// Splits one ZtringListList line: `separator` separated, `"` quoted, `""`
+// escaped.
+pub(crate) fn split_row(line: &str, separator: char) -> Vec<String> {
+    let mut columns = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if chars.peek() == Some(&'"') => {
+                chars.next();
+                current.push('"');
+            }
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => columns.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    columns.push(current);
+
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMETERS: &str = "General\nCount;;;N NI\nDuration;; ms;N YFY;;;Play time\nDuration/String;;;Y NT\nDuration/String3;;;N NT\n\nVideo\nWidth;; pixel;N YIY;;;\"Width; in pixels\"\nFormat;;;N YTY\n\nAudio\nChannels;; channel;N YIY\n";
+
+    #[test]
+    fn parses_fields_per_stream_kind() {
+        let catalog = FieldCatalog::parse(PARAMETERS);
+
+        assert_eq!(catalog.fields(MediaInfoStream::General).count(), 4);
+        assert_eq!(catalog.fields(MediaInfoStream::Video).count(), 2);
+        assert_eq!(catalog.fields(MediaInfoStream::Audio).count(), 1);
+
+        let width = catalog.get(MediaInfoStream::Video, "Width").unwrap();
+        assert_eq!(width.measure, "pixel");
+        assert_eq!(width.description, "Width; in pixels");
+        assert_eq!(width.field_type, FieldType::Integer);
+        assert!(!width.is_string_variant);
+        assert!(!catalog.contains(MediaInfoStream::Audio, "Width"));
+    }
+
+    #[test]
+    fn marks_string_variants() {
+        let catalog = FieldCatalog::parse(PARAMETERS);
+        let duration = catalog.get(MediaInfoStream::General, "Duration").unwrap();
+        let string3 = catalog
+            .get(MediaInfoStream::General, "Duration/String3")
+            .unwrap();
+
+        assert_eq!(duration.field_type, FieldType::Float);
+        assert_eq!(duration.description, "Play time");
+        assert!(string3.is_string_variant);
+        assert!(!string3.show_in_inform);
+        assert_eq!(string3.base_name(), "Duration");
+    }
+
+    #[test]
+    fn looks_up_by_name_and_prefix() {
+        let catalog = FieldCatalog::parse(PARAMETERS);
+
+        assert_eq!(
+            catalog
+                .with_prefix(MediaInfoStream::General, "Duration")
+                .len(),
+            3
+        );
+        assert_eq!(catalog.find("Format").len(), 1);
+        assert!(catalog.find("Bogus").is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+// Typed entries of the format fields that hold several values.
+//
+// `*_Format_WithHint_List` entries are a format name followed by the codec
+// ID hint in parentheses, "MPEG Audio (MP3)". `Format_Profile` entries join
+// a profile, a level and a tier with "@", "Main 10@L5.1@High"; each merged
+// layer, e.g. the HDR10 base and enhancement layers, is one entry.
+
+use crate::parsing::ListItem;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FormatName {
+    pub name: String,
+    /// The codec ID hint of the `*_WithHint_List` fields.
+    pub hint: Option<String>,
+}
+
+impl FormatName {
+    /// Parses "AAC" or, with a hint, "MPEG Audio (MP3)".
+    pub fn parse(value: &str) -> FormatName {
+        let value = value.trim();
+        let hinted = value
+            .strip_suffix(')')
+            .and_then(|x| x.rsplit_once(" ("))
+            .filter(|(name, _)| !name.is_empty());
+        match hinted {
+            Some((name, hint)) => FormatName {
+                name: name.to_string(),
+                hint: Some(hint.to_string()),
+            },
+            None => FormatName {
+                name: value.to_string(),
+                hint: None,
+            },
+        }
+    }
+}
+
+impl ListItem for FormatName {
+    fn from_item(item: &str) -> FormatName {
+        FormatName::parse(item)
+    }
+}
+
+impl fmt::Display for FormatName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.hint {
+            Some(hint) => write!(f, "{} ({hint})", self.name),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FormatProfile {
+    pub profile: String,
+    /// The level without its "L" prefix, e.g. "5.1".
+    pub level: Option<String>,
+    /// The tier, e.g. "High" or "Main" for HEVC.
+    pub tier: Option<String>,
+}
+
+impl FormatProfile {
+    /// Parses "Main 10@L5.1@High", "High@L4" or a bare profile.
+    pub fn parse(value: &str) -> FormatProfile {
+        let mut parts = value.trim().split('@').map(str::trim);
+        let profile = parts.next().unwrap_or_default().to_string();
+        let level = parts.next().filter(|x| !x.is_empty()).map(|x| {
+            x.strip_prefix('L')
+                .filter(|level| level.starts_with(|c: char| c.is_ascii_digit()))
+                .unwrap_or(x)
+                .to_string()
+        });
+        let tier = parts.next().filter(|x| !x.is_empty()).map(str::to_string);
+        FormatProfile {
+            profile,
+            level,
+            tier,
+        }
+    }
+}
+
+impl ListItem for FormatProfile {
+    fn from_item(item: &str) -> FormatProfile {
+        FormatProfile::parse(item)
+    }
+}
+
+impl fmt::Display for FormatProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.profile)?;
+        if let Some(level) = &self.level {
+            match level.starts_with(|c: char| c.is_ascii_digit()) {
+                true => write!(f, "@L{level}")?,
+                false => write!(f, "@{level}")?,
+            }
+        }
+        if let Some(tier) = &self.tier {
+            write!(f, "@{tier}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_format_names_with_hints() {
+        let hinted = FormatName::parse("MPEG Audio (MP3)");
+        assert_eq!(hinted.name, "MPEG Audio");
+        assert_eq!(hinted.hint.as_deref(), Some("MP3"));
+        assert_eq!(hinted.to_string(), "MPEG Audio (MP3)");
+
+        assert_eq!(FormatName::parse("AVC").hint, None);
+        assert_eq!(FormatName::parse("(odd)").name, "(odd)");
+    }
+
+    #[test]
+    fn parses_format_profiles() {
+        let hevc = FormatProfile::parse("Main 10@L5.1@High");
+        assert_eq!(hevc.profile, "Main 10");
+        assert_eq!(hevc.level.as_deref(), Some("5.1"));
+        assert_eq!(hevc.tier.as_deref(), Some("High"));
+        assert_eq!(hevc.to_string(), "Main 10@L5.1@High");
+
+        let mpeg2 = FormatProfile::parse("Main@Main");
+        assert_eq!(mpeg2.level.as_deref(), Some("Main"));
+        assert_eq!(mpeg2.to_string(), "Main@Main");
+
+        let bare = FormatProfile::parse("Base Media");
+        assert_eq!((bare.level, bare.tier), (None, None));
+    }
+}
@@ -0,0 +1,207 @@
+// Exact frame rates.
+//
+// MediaInfo reports `FrameRate` as a decimal with three digits ("23.976")
+// and, when the container or bitstream stores it, the exact rational in
+// `FrameRate_Num` / `FrameRate_Den`. `FrameRate` prefers the rational and
+// recognises the usual NTSC rates (n * 1000/1001) when only the decimal is
+// available.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "Fraction")]
+pub struct FrameRate {
+    numerator: u64,
+    denominator: u64,
+}
+
+// The serialized form, checked and reduced by `FrameRate::new` so that
+// deserialized rates compare and hash like the constructed ones.
+#[derive(Deserialize)]
+struct Fraction {
+    numerator: u64,
+    denominator: u64,
+}
+
+impl TryFrom<Fraction> for FrameRate {
+    type Error = &'static str;
+
+    fn try_from(value: Fraction) -> Result<FrameRate, Self::Error> {
+        FrameRate::new(value.numerator, value.denominator).ok_or("zero frame rate denominator")
+    }
+}
+
+impl FrameRate {
+    /// Returns `None` for a zero denominator. The fraction is reduced, so
+    /// `FrameRate::new(48000, 2002) == FrameRate::new(24000, 1001)`.
+    pub fn new(numerator: u64, denominator: u64) -> Option<FrameRate> {
+        if denominator == 0 {
+            return None;
+        }
+
+        let divisor = gcd(numerator, denominator);
+        Some(FrameRate {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        })
+    }
+
+    /// Parses MediaInfo's decimal rendering, e.g. "25.000" or "29.970".
+    /// Values matching an NTSC rate to the printed precision are returned
+    /// as the exact n*1000/1001 rate.
+    pub fn from_decimal(value: &str) -> Option<FrameRate> {
+        let value = value.trim();
+        let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+        if whole.is_empty() && fraction.is_empty()
+            || !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let fraction = fraction.trim_end_matches('0');
+        let scale = 10u64.checked_pow(fraction.len() as u32)?;
+        let numerator = format!("{whole}{fraction}").parse::<u64>().ok()?;
+        let exact = FrameRate::new(numerator, scale)?;
+
+        if exact.denominator != 1 && !fraction.is_empty() {
+            let decimal = exact.as_f64();
+            let nominal = (decimal * 1.001).round();
+            let tolerance = 0.5 / scale as f64 + f64::EPSILON;
+            if nominal > 0.0 && (nominal * 1000.0 / 1001.0 - decimal).abs() <= tolerance {
+                return FrameRate::new(nominal as u64 * 1000, 1001);
+            }
+        }
+
+        Some(exact)
+    }
+
+    pub fn numerator(&self) -> u64 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> u64 {
+        self.denominator
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// 24000/1001, 30000/1001, 60000/1001 and the like.
+    pub fn is_ntsc(&self) -> bool {
+        self.denominator == 1001
+    }
+
+    /// The integer rate an NTSC rate is derived from, e.g. 24 for 23.976.
+    pub fn nominal(&self) -> u64 {
+        if self.is_ntsc() {
+            self.numerator.div_ceil(1000)
+        } else {
+            self.as_f64().round() as u64
+        }
+    }
+}
+
+impl PartialOrd for FrameRate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FrameRate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.numerator as u128 * other.denominator as u128)
+            .cmp(&(other.numerator as u128 * self.denominator as u128))
+    }
+}
+
+impl fmt::Display for FrameRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduces_rationals() {
+        let rate = FrameRate::new(48000, 2002).unwrap();
+
+        assert_eq!(rate, FrameRate::new(24000, 1001).unwrap());
+        assert_eq!(rate.to_string(), "24000/1001");
+        assert_eq!(FrameRate::new(50, 2).unwrap().to_string(), "25");
+        assert_eq!(FrameRate::new(25, 0), None);
+    }
+
+    #[test]
+    fn recognises_ntsc_decimals() {
+        let film = FrameRate::from_decimal("23.976").unwrap();
+        assert_eq!(film, FrameRate::new(24000, 1001).unwrap());
+        assert!(film.is_ntsc());
+        assert_eq!(film.nominal(), 24);
+
+        assert_eq!(
+            FrameRate::from_decimal("29.970").unwrap(),
+            FrameRate::new(30000, 1001).unwrap()
+        );
+        assert_eq!(
+            FrameRate::from_decimal("59.940").unwrap(),
+            FrameRate::new(60000, 1001).unwrap()
+        );
+    }
+
+    #[test]
+    fn keeps_other_decimals_exact() {
+        let pal = FrameRate::from_decimal("25.000").unwrap();
+        assert_eq!(pal, FrameRate::new(25, 1).unwrap());
+        assert!(!pal.is_ntsc());
+
+        assert_eq!(
+            FrameRate::from_decimal("12.5").unwrap(),
+            FrameRate::new(25, 2).unwrap()
+        );
+        assert_eq!(FrameRate::from_decimal("VFR"), None);
+        assert_eq!(FrameRate::from_decimal(""), None);
+    }
+
+    #[test]
+    fn compares_and_converts() {
+        let film = FrameRate::new(24000, 1001).unwrap();
+        let cinema = FrameRate::new(24, 1).unwrap();
+
+        assert!(film < cinema);
+        assert!((film.as_f64() - 23.976).abs() < 0.001);
+    }
+
+    #[test]
+    fn deserializes_through_new() {
+        let rate: FrameRate =
+            serde_json::from_str(r#"{"numerator": 48000, "denominator": 2002}"#).unwrap();
+        assert_eq!(rate, FrameRate::new(24000, 1001).unwrap());
+        assert_eq!(rate.numerator(), 24000);
+
+        let zero = serde_json::from_str::<FrameRate>(r#"{"numerator": 25, "denominator": 0}"#);
+        assert!(zero.is_err());
+
+        let json = serde_json::to_string(&rate).unwrap();
+        assert_eq!(serde_json::from_str::<FrameRate>(&json).unwrap(), rate);
+    }
+}
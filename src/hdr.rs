@@ -0,0 +1,273 @@
+// Classification of the colour and HDR metadata MediaInfo reports for video
+// and image streams.
+//
+// When a stream carries several HDR layers (e.g. Dolby Vision with an HDR10
+// base layer) the `HDR_Format*` fields hold one " / " separated entry per
+// layer, in the same order.
+
+use crate::parsing;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HdrFormat {
+    Sdr,
+    Hdr10,
+    Hdr10Plus,
+    Hlg,
+    DolbyVision,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DolbyVisionProfile {
+    /// Sample entry style codec name, e.g. "dvhe" or "dav1".
+    pub codec: String,
+    pub profile: u8,
+    pub level: Option<u8>,
+    /// `dv_bl_signal_compatibility_id`: 1 for HDR10, 2 for SDR, 4 for HLG,
+    /// 6 for Blu-ray. Profile 8.1 is profile 8 with compatibility ID 1.
+    pub compatibility_id: Option<u8>,
+    /// Layers present, e.g. "BL+RPU".
+    pub layers: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MasteringDisplay {
+    pub color_primaries: Option<String>,
+    /// In cd/m².
+    pub min_luminance: f64,
+    /// In cd/m².
+    pub max_luminance: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HdrInfo {
+    pub format: HdrFormat,
+    /// The raw `HDR_Format_Profile` entry of the detected format.
+    pub profile: Option<String>,
+    pub dolby_vision: Option<DolbyVisionProfile>,
+    /// Formats the stream is backward compatible with, e.g. "HDR10".
+    pub compatibility: Option<String>,
+    pub colour_primaries: Option<String>,
+    pub transfer_characteristics: Option<String>,
+    pub matrix_coefficients: Option<String>,
+    pub colour_range: Option<String>,
+    pub mastering_display: Option<MasteringDisplay>,
+    /// Maximum content light level, in cd/m².
+    pub max_cll: Option<f64>,
+    /// Maximum frame average light level, in cd/m².
+    pub max_fall: Option<f64>,
+}
+
+impl HdrInfo {
+    pub fn is_hdr(&self) -> bool {
+        self.format != HdrFormat::Sdr
+    }
+
+    /// Builds the classification from a field lookup returning `None` for
+    /// empty fields.
+    pub(crate) fn from_fields(get: impl Fn(&str) -> Option<String>) -> HdrInfo {
+        let formats = split_entries(get("HDR_Format"));
+        let profiles = split_entries(get("HDR_Format_Profile"));
+        let levels = split_entries(get("HDR_Format_Level"));
+        let settings = split_entries(get("HDR_Format_Settings"));
+        let compatibilities = split_entries(get("HDR_Format_Compatibility"));
+        let transfer = get("transfer_characteristics");
+
+        let entry =
+            |entries: &[String], i: usize| entries.get(i).filter(|v| !v.is_empty()).cloned();
+        let position = |needle: &str| formats.iter().position(|f| f.contains(needle));
+
+        let (format, layer) = if let Some(i) = position("Dolby Vision") {
+            (HdrFormat::DolbyVision, Some(i))
+        } else if let Some(i) = position("SMPTE ST 2094 App 4") {
+            (HdrFormat::Hdr10Plus, Some(i))
+        } else if let Some(i) = position("SMPTE ST 2086") {
+            (HdrFormat::Hdr10, Some(i))
+        } else {
+            match transfer.as_deref() {
+                Some("PQ") => (HdrFormat::Hdr10, None),
+                Some("HLG") => (HdrFormat::Hlg, None),
+                _ => (HdrFormat::Sdr, None),
+            }
+        };
+
+        let profile = layer.and_then(|i| entry(&profiles, i));
+        let compatibility = layer.and_then(|i| entry(&compatibilities, i));
+
+        let dolby_vision = match (format, &profile) {
+            (HdrFormat::DolbyVision, Some(profile)) => {
+                parse_dolby_vision_profile(profile).map(|(codec, number)| DolbyVisionProfile {
+                    codec,
+                    profile: number,
+                    level: layer
+                        .and_then(|i| entry(&levels, i))
+                        .and_then(|l| l.parse().ok()),
+                    compatibility_id: compatibility.as_deref().and_then(compatibility_id),
+                    layers: layer.and_then(|i| entry(&settings, i)),
+                })
+            }
+            _ => None,
+        };
+
+        let mastering_display = get("MasteringDisplay_Luminance")
+            .as_deref()
+            .and_then(parse_luminance_range)
+            .map(|(min_luminance, max_luminance)| MasteringDisplay {
+                color_primaries: get("MasteringDisplay_ColorPrimaries"),
+                min_luminance,
+                max_luminance,
+            });
+
+        HdrInfo {
+            format,
+            profile,
+            dolby_vision,
+            compatibility,
+            colour_primaries: get("colour_primaries"),
+            transfer_characteristics: transfer,
+            matrix_coefficients: get("matrix_coefficients"),
+            colour_range: get("colour_range"),
+            mastering_display,
+            max_cll: get("MaxCLL").as_deref().and_then(parse_light_level),
+            max_fall: get("MaxFALL").as_deref().and_then(parse_light_level),
+        }
+    }
+}
+
+fn split_entries(value: Option<String>) -> Vec<String> {
+    match value {
+        Some(v) => v.split(" / ").map(|s| s.trim().to_string()).collect(),
+        None => Vec::new(),
+    }
+}
+
+// "dvhe.08" -> ("dvhe", 8)
+fn parse_dolby_vision_profile(profile: &str) -> Option<(String, u8)> {
+    let (codec, number) = profile.split_once('.')?;
+    let number = number.split('.').next()?.parse().ok()?;
+    Some((codec.to_string(), number))
+}
+
+// Inverse of MediaInfoLib's DolbyVision_Compatibility table; unknown IDs are
+// reported as plain numbers.
+fn compatibility_id(compatibility: &str) -> Option<u8> {
+    match compatibility {
+        "HDR10" => Some(1),
+        "SDR" => Some(2),
+        "HLG" => Some(4),
+        "Blu-ray" => Some(6),
+        other => other.parse().ok(),
+    }
+}
+
+// "min: 0.0050 cd/m2, max: 1000 cd/m2"
+fn parse_luminance_range(value: &str) -> Option<(f64, f64)> {
+    let mut min = None;
+    let mut max = None;
+
+    for part in value.split(',') {
+        let (key, number) = part.trim().split_once(':')?;
+        let number = parse_light_level(number)?;
+        match key.trim() {
+            "min" => min = Some(number),
+            "max" => max = Some(number),
+            _ => {}
+        }
+    }
+
+    Some((min?, max?))
+}
+
+// "1000 cd/m2"
+fn parse_light_level(value: &str) -> Option<f64> {
+    parsing::parse_f64(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::VideoSnapshot;
+
+    fn classify(fields: &[(&str, &str)]) -> HdrInfo {
+        let video = VideoSnapshot::from_pairs(0, fields);
+        HdrInfo::from_fields(|name| video.get(name).map(str::to_string))
+    }
+
+    #[test]
+    fn classifies_dolby_vision_with_hdr10_base_layer() {
+        let info = classify(&[
+            ("HDR_Format", "Dolby Vision / SMPTE ST 2086"),
+            ("HDR_Format_Version", "1.0 / "),
+            ("HDR_Format_Profile", "dvhe.08 / "),
+            ("HDR_Format_Level", "06 / "),
+            ("HDR_Format_Settings", "BL+RPU / "),
+            ("HDR_Format_Compatibility", "HDR10 / HDR10"),
+            ("transfer_characteristics", "PQ"),
+            (
+                "MasteringDisplay_Luminance",
+                "min: 0.0050 cd/m2, max: 1000 cd/m2",
+            ),
+            ("MasteringDisplay_ColorPrimaries", "Display P3"),
+            ("MaxCLL", "1000 cd/m2"),
+            ("MaxFALL", "400 cd/m2"),
+        ]);
+
+        assert_eq!(info.format, HdrFormat::DolbyVision);
+        assert_eq!(info.profile.as_deref(), Some("dvhe.08"));
+        assert_eq!(
+            info.dolby_vision,
+            Some(DolbyVisionProfile {
+                codec: "dvhe".to_string(),
+                profile: 8,
+                level: Some(6),
+                compatibility_id: Some(1),
+                layers: Some("BL+RPU".to_string()),
+            })
+        );
+
+        let mastering = info.mastering_display.unwrap();
+        assert_eq!(mastering.min_luminance, 0.005);
+        assert_eq!(mastering.max_luminance, 1000.0);
+        assert_eq!(info.max_cll, Some(1000.0));
+        assert_eq!(info.max_fall, Some(400.0));
+    }
+
+    #[test]
+    fn classifies_static_and_dynamic_metadata() {
+        let hdr10 = classify(&[
+            ("HDR_Format", "SMPTE ST 2086"),
+            ("HDR_Format_Compatibility", "HDR10"),
+            ("transfer_characteristics", "PQ"),
+        ]);
+        assert_eq!(hdr10.format, HdrFormat::Hdr10);
+        assert_eq!(hdr10.compatibility.as_deref(), Some("HDR10"));
+
+        let hdr10_plus = classify(&[
+            ("HDR_Format", "SMPTE ST 2094 App 4 / SMPTE ST 2086"),
+            ("HDR_Format_Compatibility", "HDR10+ Profile B / HDR10"),
+        ]);
+        assert_eq!(hdr10_plus.format, HdrFormat::Hdr10Plus);
+        assert_eq!(
+            hdr10_plus.compatibility.as_deref(),
+            Some("HDR10+ Profile B")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_transfer_characteristics() {
+        assert_eq!(
+            classify(&[("transfer_characteristics", "HLG")]).format,
+            HdrFormat::Hlg
+        );
+        assert_eq!(
+            classify(&[("transfer_characteristics", "PQ")]).format,
+            HdrFormat::Hdr10
+        );
+
+        let sdr = classify(&[("transfer_characteristics", "BT.709")]);
+        assert_eq!(sdr.format, HdrFormat::Sdr);
+        assert!(!sdr.is_hdr());
+        assert!(sdr.mastering_display.is_none());
+    }
+}
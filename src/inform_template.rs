@@ -0,0 +1,410 @@
+// Typed builder for MediaInfo custom `Inform` templates.
+//
+// libmediainfo accepts a template through `Option("Inform", ...)` made of
+// one line per section (`Video;%Width%x%Height%`). The section body is
+// parsed by the library with its own escaping rules: `\` `[` `]` `,` `;`
+// `(` `)` must be backslash-escaped in literal text, `\n` stands for a line
+// break and the whole line is split on `;` unless the body is quoted.
+// This module hides all of that behind a small builder.
+
+use crate::ffi::{MediaInfoError, MediaInfoResult, MediaInfoStream};
+use crate::field_catalog::FieldCatalog;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TemplateItem {
+    Text(String),
+    Field(String),
+    IfPresent {
+        field: String,
+        then: Vec<TemplateItem>,
+        otherwise: Vec<TemplateItem>,
+    },
+}
+
+/// The body of one template section: a sequence of literal text, field
+/// references and `$if()` conditionals.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Section {
+    items: Vec<TemplateItem>,
+}
+
+impl Section {
+    pub fn new() -> Section {
+        Default::default()
+    }
+
+    pub fn text(mut self, text: &str) -> Section {
+        self.items.push(TemplateItem::Text(text.to_string()));
+        self
+    }
+
+    pub fn newline(self) -> Section {
+        self.text("\n")
+    }
+
+    pub fn field(mut self, name: &str) -> Section {
+        self.items.push(TemplateItem::Field(name.to_string()));
+        self
+    }
+
+    /// Renders `then` when `field` has a value for the stream, `otherwise`
+    /// when it is empty. MediaInfo does not support nested conditionals.
+    pub fn if_present(mut self, field: &str, then: Section, otherwise: Section) -> Section {
+        self.items.push(TemplateItem::IfPresent {
+            field: field.to_string(),
+            then: then.items,
+            otherwise: otherwise.items,
+        });
+        self
+    }
+
+    pub fn items(&self) -> &[TemplateItem] {
+        &self.items
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SectionPart {
+    Body,
+    Begin,
+    Middle,
+    End,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct TemplateSection {
+    kind: MediaInfoStream,
+    part: SectionPart,
+    section: Section,
+}
+
+/// A custom `Inform` template, rendered per stream kind.
+///
+/// ```ignore
+/// let template = InformTemplate::new().section(
+///     MediaInfoStream::Video,
+///     Section::new()
+///         .field("Width")
+///         .text("x")
+///         .field("Height")
+///         .text(" ")
+///         .field("FrameRate")
+///         .text(" fps")
+///         .newline(),
+/// );
+/// let output = media_info.inform_with_template(&template)?;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InformTemplate {
+    sections: Vec<TemplateSection>,
+}
+
+impl InformTemplate {
+    pub fn new() -> InformTemplate {
+        Default::default()
+    }
+
+    /// Sets the template applied to every stream of `kind`.
+    pub fn section(self, kind: MediaInfoStream, section: Section) -> InformTemplate {
+        self.with_part(kind, SectionPart::Body, section)
+    }
+
+    /// Literal text printed once before the streams of `kind`.
+    pub fn begin(self, kind: MediaInfoStream, text: &str) -> InformTemplate {
+        self.with_part(kind, SectionPart::Begin, Section::new().text(text))
+    }
+
+    /// Literal text printed between two consecutive streams of `kind`.
+    pub fn separator(self, kind: MediaInfoStream, text: &str) -> InformTemplate {
+        self.with_part(kind, SectionPart::Middle, Section::new().text(text))
+    }
+
+    /// Literal text printed once after the streams of `kind`.
+    pub fn end(self, kind: MediaInfoStream, text: &str) -> InformTemplate {
+        self.with_part(kind, SectionPart::End, Section::new().text(text))
+    }
+
+    fn with_part(mut self, kind: MediaInfoStream, part: SectionPart, section: Section) -> Self {
+        self.sections
+            .retain(|s| !(s.kind == kind && s.part == part));
+        self.sections.push(TemplateSection {
+            kind,
+            part,
+            section,
+        });
+        self
+    }
+
+    /// Every field referenced by the template, grouped by stream kind.
+    pub fn fields(&self) -> Vec<(MediaInfoStream, String)> {
+        let mut fields = Vec::new();
+        for s in self.sections.iter().filter(|s| s.part == SectionPart::Body) {
+            collect_fields(&s.section.items, &mut |name| {
+                fields.push((s.kind, name.to_string()));
+            });
+        }
+        fields
+    }
+
+    /// Checks every referenced field against the fields the linked library
+    /// provides for the section's stream kind.
+    pub fn validate(&self, catalog: &FieldCatalog) -> MediaInfoResult<()> {
+        for (kind, name) in self.fields() {
+            if !catalog.contains(kind, &name) {
+                return Err(MediaInfoError::UnknownParameter(format!(
+                    "{}/{}",
+                    kind.name(),
+                    name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Renders the value to pass to `Option("Inform", ...)`.
+    pub fn render(&self) -> MediaInfoResult<String> {
+        let mut lines = Vec::new();
+
+        for s in &self.sections {
+            if matches!(s.kind, MediaInfoStream::Max) {
+                return Err(MediaInfoError::InvalidTemplate(
+                    "Max is not a stream kind".to_string(),
+                ));
+            }
+
+            let name = match s.part {
+                SectionPart::Body => s.kind.name().to_string(),
+                SectionPart::Begin => format!("{}_Begin", s.kind.name()),
+                SectionPart::Middle => format!("{}_Middle", s.kind.name()),
+                SectionPart::End => format!("{}_End", s.kind.name()),
+            };
+
+            let body = match s.part {
+                // Begin/Middle/End are copied verbatim, only line breaks are translated.
+                SectionPart::Body => render_items(&s.section.items, false)?,
+                _ => render_plain(&s.section.items),
+            };
+
+            lines.push(format!("{};{}", name, quote_body(&body)));
+        }
+
+        Ok(lines.join("\n"))
+    }
+}
+
+fn collect_fields(items: &[TemplateItem], f: &mut impl FnMut(&str)) {
+    for item in items {
+        match item {
+            TemplateItem::Text(_) => {}
+            TemplateItem::Field(name) => f(name),
+            TemplateItem::IfPresent {
+                field,
+                then,
+                otherwise,
+            } => {
+                f(field);
+                collect_fields(then, f);
+                collect_fields(otherwise, f);
+            }
+        }
+    }
+}
+
+fn render_items(items: &[TemplateItem], in_condition: bool) -> MediaInfoResult<String> {
+    let mut out = String::new();
+
+    for item in items {
+        match item {
+            TemplateItem::Text(text) => out.push_str(&escape_text(text)?),
+            TemplateItem::Field(name) => out.push_str(&field_reference(name, in_condition)?),
+            TemplateItem::IfPresent {
+                field,
+                then,
+                otherwise,
+            } => {
+                if in_condition {
+                    return Err(MediaInfoError::InvalidTemplate(
+                        "$if() cannot be nested".to_string(),
+                    ));
+                }
+                out.push_str("$if(");
+                out.push_str(&field_reference(field, true)?);
+                out.push(',');
+                out.push_str(&render_items(then, true)?);
+                out.push(',');
+                out.push_str(&render_items(otherwise, true)?);
+                out.push(')');
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+fn render_plain(items: &[TemplateItem]) -> String {
+    let mut out = String::new();
+    for item in items {
+        if let TemplateItem::Text(text) = item {
+            out.push_str(&text.replace("\r\n", "\\n").replace('\n', "\\n"));
+        }
+    }
+    out
+}
+
+fn field_reference(name: &str, in_condition: bool) -> MediaInfoResult<String> {
+    let starts_with_letter = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic());
+    let forbidden: &[char] = if in_condition {
+        &['%', '$', ';', '\\', '[', ']', ',', '(', ')', '\n', '\r']
+    } else {
+        &['%', '$', ';', '\\', '[', ']', '\n', '\r']
+    };
+
+    if !starts_with_letter || name.contains(forbidden) {
+        return Err(MediaInfoError::InvalidTemplate(format!(
+            "field name {name:?} cannot be used here"
+        )));
+    }
+
+    Ok(format!("%{name}%"))
+}
+
+fn escape_text(text: &str) -> MediaInfoResult<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            // Neither has an escape sequence in MediaInfo templates.
+            '%' | '$' => {
+                return Err(MediaInfoError::InvalidTemplate(format!(
+                    "literal {c:?} is not supported in template text"
+                )));
+            }
+            '\\' | '[' | ']' | ',' | ';' | '(' | ')' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\r' => {
+                if chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                out.push_str("\\n");
+            }
+            '\n' => out.push_str("\\n"),
+            _ => out.push(c),
+        }
+    }
+
+    Ok(out)
+}
+
+// Each template line is read by a ZtringListList which splits on `;` and
+// treats `"` as a quote, so bodies containing either must be quoted.
+fn quote_body(body: &str) -> String {
+    if body.contains(';') || body.contains('"') {
+        format!("\"{}\"", body.replace('"', "\"\""))
+    } else {
+        body.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMETERS: &str = "General\nCount;;;N NI\nFormat;;;N YTY\n\nVideo\nWidth;; pixel;N YIY\nHeight;; pixel;N YIY\nFrameRate;; fps;N YFY\n\nAudio\nLanguage;;;N YTY\nChannels;; channel;N YIY\n";
+
+    #[test]
+    fn renders_fields_and_text() {
+        let template = InformTemplate::new().section(
+            MediaInfoStream::Video,
+            Section::new()
+                .field("Width")
+                .text("x")
+                .field("Height")
+                .text(" ")
+                .field("FrameRate")
+                .text(" fps")
+                .newline(),
+        );
+
+        assert_eq!(
+            "Video;%Width%x%Height% %FrameRate% fps\\n",
+            template.render().unwrap()
+        );
+    }
+
+    #[test]
+    fn escapes_separators() {
+        let template = InformTemplate::new().section(
+            MediaInfoStream::Audio,
+            Section::new()
+                .text("a;b, (c) [d] \\ \"e\"")
+                .field("Channels"),
+        );
+
+        assert_eq!(
+            "Audio;\"a\\;b\\, \\(c\\) \\[d\\] \\\\ \"\"e\"\"%Channels%\"",
+            template.render().unwrap()
+        );
+    }
+
+    #[test]
+    fn renders_conditionals_and_parts() {
+        let template = InformTemplate::new()
+            .begin(MediaInfoStream::Audio, "Audio:\n")
+            .section(
+                MediaInfoStream::Audio,
+                Section::new().if_present(
+                    "Language",
+                    Section::new().text("lang=").field("Language"),
+                    Section::new().text("und"),
+                ),
+            )
+            .separator(MediaInfoStream::Audio, ", ");
+
+        assert_eq!(
+            "Audio_Begin;Audio:\\n\nAudio;$if(%Language%,lang=%Language%,und)\nAudio_Middle;, ",
+            template.render().unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_nested_conditionals_and_literal_percent() {
+        let nested = InformTemplate::new().section(
+            MediaInfoStream::Video,
+            Section::new().if_present(
+                "Width",
+                Section::new().if_present("Height", Section::new(), Section::new()),
+                Section::new(),
+            ),
+        );
+        assert!(matches!(
+            nested.render(),
+            Err(MediaInfoError::InvalidTemplate(_))
+        ));
+
+        let percent =
+            InformTemplate::new().section(MediaInfoStream::Video, Section::new().text("100%"));
+        assert!(matches!(
+            percent.render(),
+            Err(MediaInfoError::InvalidTemplate(_))
+        ));
+    }
+
+    #[test]
+    fn validates_fields_per_stream_kind() {
+        let ok = InformTemplate::new()
+            .section(MediaInfoStream::General, Section::new().field("Format"))
+            .section(MediaInfoStream::Video, Section::new().field("Width"));
+        assert!(ok.validate(&FieldCatalog::parse(PARAMETERS)).is_ok());
+
+        let wrong_kind =
+            InformTemplate::new().section(MediaInfoStream::Audio, Section::new().field("Width"));
+        assert!(matches!(
+            wrong_kind.validate(&FieldCatalog::parse(PARAMETERS)),
+            Err(MediaInfoError::UnknownParameter(name)) if name == "Audio/Width"
+        ));
+    }
+}
@@ -0,0 +1,323 @@
+// BCP-47 language tags for the `Language` fields.
+//
+// Depending on the container, MediaInfo reports a language as an ISO 639-1
+// code ("en"), an ISO 639-2 code in its bibliographic or terminology form
+// ("fre", "fra"), a tag with a region ("en-US") or a full English name.
+// `LanguageTag` normalises all of them to the shortest BCP-47 form and keeps
+// the value it was parsed from.
+
+use crate::parsing::ListItem;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+include!(concat!(env!("OUT_DIR"), "/language_tables.rs"));
+
+// ISO 639-2 codes whose bibliographic (B) form differs from the terminology
+// (T) form, as (B, T).
+const BIBLIOGRAPHIC_CODES: &[(&str, &str)] = &[
+    ("alb", "sqi"),
+    ("arm", "hye"),
+    ("baq", "eus"),
+    ("bur", "mya"),
+    ("chi", "zho"),
+    ("cze", "ces"),
+    ("dut", "nld"),
+    ("fre", "fra"),
+    ("geo", "kat"),
+    ("ger", "deu"),
+    ("gre", "ell"),
+    ("ice", "isl"),
+    ("mac", "mkd"),
+    ("mao", "mri"),
+    ("may", "msa"),
+    ("per", "fas"),
+    ("rum", "ron"),
+    ("slo", "slk"),
+    ("tib", "bod"),
+    ("wel", "cym"),
+];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LanguageTag {
+    tag: String,
+    original: String,
+}
+
+impl LanguageTag {
+    /// Parses a code, a tag or an English language name. When several
+    /// values are joined with " / " the first one is used.
+    pub fn parse(value: &str) -> Option<LanguageTag> {
+        let original = value.split(" / ").next()?.trim();
+        if original.is_empty() {
+            return None;
+        }
+
+        let tag = normalize_tag(original).or_else(|| {
+            LANGUAGE_NAMES
+                .iter()
+                .find(|(_, name)| name.eq_ignore_ascii_case(original))
+                .and_then(|(code, _)| normalize_tag(code))
+        })?;
+
+        Some(LanguageTag {
+            tag,
+            original: original.to_string(),
+        })
+    }
+
+    /// The normalised BCP-47 tag, e.g. "en-US".
+    pub fn as_str(&self) -> &str {
+        &self.tag
+    }
+
+    /// The value as MediaInfo reported it.
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    /// The primary language subtag, e.g. "en" for "en-US".
+    pub fn language(&self) -> &str {
+        self.tag.split('-').next().unwrap_or_default()
+    }
+
+    /// The four letter script subtag, e.g. "Hant" for "zh-Hant".
+    pub fn script(&self) -> Option<&str> {
+        self.subtags().find(|s| s.len() == 4 && is_alpha(s))
+    }
+
+    /// The region subtag, e.g. "US" for "en-US" or "419" for "es-419".
+    pub fn region(&self) -> Option<&str> {
+        self.subtags()
+            .find(|s| (s.len() == 2 && is_alpha(s)) || (s.len() == 3 && is_digit(s)))
+    }
+
+    pub fn iso639_1(&self) -> Option<&str> {
+        let language = self.language();
+        (language.len() == 2).then_some(language)
+    }
+
+    /// The ISO 639-2/T code, e.g. "fra" for French.
+    pub fn iso639_2t(&self) -> Option<&str> {
+        let language = self.language();
+        if language.len() == 3 {
+            return Some(language);
+        }
+        ISO639_2_TO_1
+            .iter()
+            .find(|(three, two)| {
+                *two == language && !BIBLIOGRAPHIC_CODES.iter().any(|(b, _)| b == three)
+            })
+            .map(|(three, _)| *three)
+    }
+
+    /// The ISO 639-2/B code, e.g. "fre" for French. Same as the
+    /// terminology code for most languages.
+    pub fn iso639_2b(&self) -> Option<&str> {
+        let terminology = self.iso639_2t()?;
+        Some(
+            BIBLIOGRAPHIC_CODES
+                .iter()
+                .find(|(_, t)| *t == terminology)
+                .map_or(terminology, |(b, _)| *b),
+        )
+    }
+
+    /// The English name of the language, with the region when MediaInfo
+    /// knows that combination, e.g. "English (United States)".
+    pub fn name(&self) -> Option<&'static str> {
+        let find = |code: &str| {
+            LANGUAGE_NAMES
+                .iter()
+                .find(|(c, _)| c.eq_ignore_ascii_case(code))
+                .map(|(_, name)| *name)
+        };
+        find(&self.tag).or_else(|| find(self.language()))
+    }
+
+    /// Basic filtering as in RFC 4647: "en" matches "en" and "en-US" but
+    /// not "eng-x". The range may use any form `parse` accepts, and "*"
+    /// matches every tag.
+    pub fn matches(&self, range: &str) -> bool {
+        if range.trim() == "*" {
+            return true;
+        }
+        let Some(range) = LanguageTag::parse(range) else {
+            return false;
+        };
+
+        let tag = self.tag.to_ascii_lowercase();
+        let range = range.tag.to_ascii_lowercase();
+        tag == range || tag.starts_with(&format!("{range}-"))
+    }
+
+    fn subtags(&self) -> impl Iterator<Item = &str> {
+        self.tag.split('-').skip(1)
+    }
+}
+
+impl PartialEq for LanguageTag {
+    fn eq(&self, other: &Self) -> bool {
+        self.tag == other.tag
+    }
+}
+
+impl Eq for LanguageTag {}
+
+impl Hash for LanguageTag {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.tag.hash(state);
+    }
+}
+
+// `*_Language_List` entries. Streams without a language, or with one that
+// is not recognised, are undetermined ("und") so positions still line up
+// with the streams.
+impl ListItem for LanguageTag {
+    fn from_item(item: &str) -> LanguageTag {
+        LanguageTag::parse(item).unwrap_or_else(|| LanguageTag {
+            tag: "und".to_string(),
+            original: item.trim().to_string(),
+        })
+    }
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.tag)
+    }
+}
+
+fn is_alpha(s: &str) -> bool {
+    s.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+fn is_digit(s: &str) -> bool {
+    s.chars().all(|c| c.is_ascii_digit())
+}
+
+// ISO 639-2 codes for content without a single known language.
+const SPECIAL_CODES: &[&str] = &["mis", "mul", "und", "zxx"];
+
+// Whether `code` is a lowercase ISO 639-1 or ISO 639-2 code the tables
+// know, so that words such as "act" or "day" are not taken for languages.
+fn is_known_code(code: &str) -> bool {
+    ISO639_2_TO_1
+        .iter()
+        .any(|(three, two)| *three == code || *two == code)
+        || BIBLIOGRAPHIC_CODES
+            .iter()
+            .any(|(b, t)| *b == code || *t == code)
+        || LANGUAGE_NAMES
+            .iter()
+            .any(|(key, _)| key.split('-').next() == Some(code))
+        || SPECIAL_CODES.contains(&code)
+}
+
+// "FRE" -> "fr", "en_us" -> "en-US", "zh-hant-tw" -> "zh-Hant-TW".
+fn normalize_tag(value: &str) -> Option<String> {
+    let mut parts = value.split(['-', '_']);
+    let primary = parts.next()?.to_ascii_lowercase();
+    if !(2..=3).contains(&primary.len()) || !is_alpha(&primary) || !is_known_code(&primary) {
+        return None;
+    }
+
+    let primary = if primary.len() == 3 {
+        ISO639_2_TO_1
+            .iter()
+            .find(|(three, _)| *three == primary)
+            .map(|(_, two)| two.to_string())
+            .or_else(|| {
+                BIBLIOGRAPHIC_CODES
+                    .iter()
+                    .find(|(b, _)| *b == primary)
+                    .map(|(_, t)| t.to_string())
+            })
+            .unwrap_or(primary)
+    } else {
+        primary
+    };
+
+    let mut tag = primary;
+    for subtag in parts {
+        if subtag.is_empty() || !subtag.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        tag.push('-');
+        match subtag.len() {
+            4 if is_alpha(subtag) => {
+                tag.push_str(&subtag[..1].to_ascii_uppercase());
+                tag.push_str(&subtag[1..].to_ascii_lowercase());
+            }
+            2 if is_alpha(subtag) => tag.push_str(&subtag.to_ascii_uppercase()),
+            _ => tag.push_str(&subtag.to_ascii_lowercase()),
+        }
+    }
+
+    Some(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_codes_and_names() {
+        for value in ["en", "EN", "eng", "English", "english"] {
+            assert_eq!(LanguageTag::parse(value).unwrap().as_str(), "en");
+        }
+        for value in ["fr", "fre", "fra", "French"] {
+            assert_eq!(LanguageTag::parse(value).unwrap().as_str(), "fr");
+        }
+
+        assert_eq!(LanguageTag::parse("en_us").unwrap().as_str(), "en-US");
+        assert_eq!(
+            LanguageTag::parse("English (United States)")
+                .unwrap()
+                .as_str(),
+            "en-US"
+        );
+        assert_eq!(
+            LanguageTag::parse("zh-hant-tw").unwrap().as_str(),
+            "zh-Hant-TW"
+        );
+        assert_eq!(LanguageTag::parse("Klingon (Qo'noS)"), None);
+        assert_eq!(LanguageTag::parse("und").unwrap().as_str(), "und");
+        for word in ["act", "Day", "end", "xx"] {
+            assert_eq!(LanguageTag::parse(word), None, "{word}");
+        }
+        assert_eq!(LanguageTag::parse(""), None);
+    }
+
+    #[test]
+    fn maps_between_code_forms() {
+        let german = LanguageTag::parse("ger").unwrap();
+        assert_eq!(german.original(), "ger");
+        assert_eq!(german.iso639_1(), Some("de"));
+        assert_eq!(german.iso639_2t(), Some("deu"));
+        assert_eq!(german.iso639_2b(), Some("ger"));
+        assert_eq!(german.name(), Some("German"));
+
+        let spanish = LanguageTag::parse("es-419").unwrap();
+        assert_eq!(spanish.region(), Some("419"));
+        assert_eq!(spanish.iso639_2b(), Some("spa"));
+
+        let chinese = LanguageTag::parse("zh-Hant").unwrap();
+        assert_eq!(chinese.script(), Some("Hant"));
+        assert_eq!(chinese.region(), None);
+    }
+
+    #[test]
+    fn matches_language_ranges() {
+        let tag = LanguageTag::parse("en-US").unwrap();
+
+        assert!(tag.matches("en"));
+        assert!(tag.matches("eng"));
+        assert!(tag.matches("en-us"));
+        assert!(tag.matches("*"));
+        assert!(!tag.matches("en-GB"));
+        assert!(!tag.matches("fr"));
+        assert_eq!(tag, LanguageTag::parse("en_US").unwrap());
+    }
+}
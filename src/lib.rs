@@ -4,15 +4,127 @@ extern crate libc;
 #[macro_use]
 extern crate delegate;
 
+mod batch;
 mod c_w_string;
+mod cache;
+mod capabilities;
+mod channel_layout;
+mod chapter;
+mod classification;
 mod convenience_api;
+mod date;
+mod diff;
 mod ffi;
+mod field_catalog;
+mod format;
+mod frame_rate;
+mod hdr;
+mod inform_template;
+mod language;
+mod parsing;
+mod policy;
+mod program;
+mod report;
+#[cfg(feature = "server")]
+mod server;
+mod snapshot;
 mod streams;
+mod timecode;
+mod track_selection;
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+mod watch;
 
 pub type MediaInfoWrapper = convenience_api::MediaInfoWrapper;
 pub type MediaInfo = ffi::MediaInfo;
 pub type MediaInfoResult<T> = ffi::MediaInfoResult<T>;
 pub type MediaInfoError = ffi::MediaInfoError;
+pub type MediaInfoStream = ffi::MediaInfoStream;
+pub type LibraryVersion = capabilities::LibraryVersion;
+pub type Codec = capabilities::Codec;
+pub type OutputFormat = capabilities::OutputFormat;
+pub type PluginState = capabilities::PluginState;
+pub const VENDORED_VERSION: &str = capabilities::VENDORED_VERSION;
+pub type FieldCatalog = field_catalog::FieldCatalog;
+pub type FieldInfo = field_catalog::FieldInfo;
+pub type FieldType = field_catalog::FieldType;
+pub type InformTemplate = inform_template::InformTemplate;
+pub type InformSection = inform_template::Section;
+pub type InformTemplateItem = inform_template::TemplateItem;
+pub type FrameRate = frame_rate::FrameRate;
+pub type MediaDate = date::MediaDate;
+pub type LanguageTag = language::LanguageTag;
+pub type FormatName = format::FormatName;
+pub type FormatProfile = format::FormatProfile;
+pub type ChannelLayout = channel_layout::ChannelLayout;
+pub type Speaker = channel_layout::Speaker;
+pub type Chapter = chapter::Chapter;
+pub type ChapterTitle = chapter::ChapterTitle;
+pub type Timecode = timecode::Timecode;
+pub type Policy = policy::Policy;
+pub type PolicyRule = policy::Rule;
+pub type PolicyCondition = policy::Condition;
+pub type PolicyReport = policy::PolicyReport;
+pub type RuleResult = policy::RuleResult;
+pub type Program = program::Program;
+pub type ProgramStream = program::ProgramStream;
+pub type ProgramTrack = program::ProgramTrack;
+pub type TrackSelector = track_selection::TrackSelector;
+pub type TrackSelection = track_selection::TrackSelection;
+pub type SelectedTrack = track_selection::SelectedTrack;
+pub type SelectionReason = track_selection::SelectionReason;
+pub type ChannelPreference = track_selection::ChannelPreference;
+pub type SubtitlePreference = track_selection::SubtitlePreference;
+pub type MediaClass = classification::MediaClass;
+pub type ResolutionClass = classification::ResolutionClass;
+pub type ImmersiveAudio = classification::ImmersiveAudio;
+pub type HdrInfo = hdr::HdrInfo;
+pub type HdrFormat = hdr::HdrFormat;
+pub type DolbyVisionProfile = hdr::DolbyVisionProfile;
+pub type MasteringDisplay = hdr::MasteringDisplay;
+pub type BatchAnalyzer = batch::BatchAnalyzer;
+pub type BatchRecord = batch::BatchRecord;
+pub type BatchStatus = batch::BatchStatus;
+pub type BatchSummary = batch::BatchSummary;
+pub type OpenError = batch::OpenError;
+pub type AnalysisCache = cache::AnalysisCache;
+pub type FileIdentity = cache::FileIdentity;
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+pub type WatchFolder = watch::WatchFolder;
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+pub type WatchOutput = watch::WatchOutput;
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+pub type WatchRecord = watch::WatchRecord;
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+pub type Completion = watch::Completion;
+#[cfg(feature = "server")]
+pub type AnalysisServer = server::AnalysisServer;
+#[cfg(feature = "server")]
+pub type ServerOptions = server::ServerOptions;
+pub type MediaDiff = diff::MediaDiff;
+pub type DiffOptions = diff::DiffOptions;
+pub type DiffStatus = diff::DiffStatus;
+pub type StreamDiff = diff::StreamDiff;
+pub type FieldDiff = diff::FieldDiff;
+pub type StreamAlignment = diff::StreamAlignment;
+pub type MediaSnapshot = snapshot::MediaSnapshot;
+pub type GeneralSnapshot = snapshot::GeneralSnapshot;
+pub type VideoSnapshot = snapshot::VideoSnapshot;
+pub type AudioSnapshot = snapshot::AudioSnapshot;
+pub type TextSnapshot = snapshot::TextSnapshot;
+pub type OtherSnapshot = snapshot::OtherSnapshot;
+pub type ImageSnapshot = snapshot::ImageSnapshot;
+pub type MenuSnapshot = snapshot::MenuSnapshot;
+pub type MediaInfoReport = report::MediaInfoReport;
+pub type ReportTrack = report::Track;
+pub type ReportMedia = report::Media;
+pub type CreatingLibrary = report::CreatingLibrary;
+pub type GeneralTrack = report::GeneralTrack;
+pub type VideoTrack = report::VideoTrack;
+pub type AudioTrack = report::AudioTrack;
+pub type TextTrack = report::TextTrack;
+pub type OtherTrack = report::OtherTrack;
+pub type ImageTrack = report::ImageTrack;
+pub type MenuTrack = report::MenuTrack;
 
 #[cfg(test)]
 mod tests {
@@ -21,6 +133,7 @@ mod tests {
     use std::path::PathBuf;
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn can_retrieve_stream_count() {
         let sample_path = PathBuf::from("samples");
         let filename = sample_path.join("sample.mp4");
@@ -33,4 +146,35 @@ mod tests {
 
         m.close();
     }
+
+    #[test]
+    fn restores_options_after_use() {
+        let mut m = MediaInfo::new();
+        let complete = m
+            .with_options(&[("Complete", "1")], |m| m.option("Complete_Get", ""))
+            .unwrap();
+
+        assert_eq!(complete, "1");
+        assert!(m.option("Complete_Get", "").is_err());
+        assert!(matches!(
+            m.with_options(&[("NoSuchOption", "1")], |m| m.inform()),
+            Err(MediaInfoError::UnknownParameter(_))
+        ));
+    }
+
+    #[test]
+    fn can_build_field_catalog() {
+        let mut m = MediaInfo::new();
+        let catalog = m.field_catalog().unwrap();
+
+        let width = catalog.get(MediaInfoStream::Video, "Width").unwrap();
+        assert_eq!(width.measure, "pixel");
+        assert!(catalog.contains(MediaInfoStream::Audio, "Channels"));
+        assert!(!catalog.contains(MediaInfoStream::Audio, "Width"));
+        assert!(
+            !catalog
+                .with_prefix(MediaInfoStream::Video, "HDR_Format")
+                .is_empty()
+        );
+    }
 }
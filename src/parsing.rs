@@ -0,0 +1,295 @@
+// Conversions from the strings MediaInfo returns to numbers and durations.
+//
+// Values are accepted in the forms the library produces depending on the
+// parser and on the `Legacy` / `ReadByHuman` options: plain or decimal
+// numbers ("5568", "5568.000"), several values joined with " / " (the
+// first one is used), a trailing measure ("1000 cd/m2", "25 %"), digit
+// grouping with spaces ("1 234 567") and, for durations, the
+// "HH:MM:SS.mmm" and "1 h 2 min 3 s 456 ms" renderings.
+
+use std::time::Duration;
+
+/// MediaInfo's default `TagSeparator`, placed between the values of a field
+/// filled more than once and between the entries of the `*_List` fields.
+pub(crate) const DEFAULT_SEPARATOR: &str = " / ";
+
+// The first of several " / " separated values, without surrounding spaces.
+fn first_value(value: &str) -> &str {
+    value.split(" / ").next().unwrap_or_default().trim()
+}
+
+// Keeps the leading number of `value`, dropping digit grouping spaces and
+// any trailing measure. "-1 234.5 kb/s" -> "-1234.5".
+fn numeric_prefix(value: &str) -> Option<String> {
+    let mut number = String::new();
+    let mut chars = first_value(value).chars().peekable();
+
+    if let Some(&sign) = chars.peek()
+        && (sign == '-' || sign == '+')
+    {
+        number.push(sign);
+        chars.next();
+    }
+
+    while let Some(c) = chars.next() {
+        match c {
+            '0'..='9' => number.push(c),
+            '.' if !number.contains('.') => number.push(c),
+            // Grouping separators only count when a digit follows.
+            ' ' | '\u{a0}' | '\u{202f}' if chars.peek().is_some_and(|n| n.is_ascii_digit()) => {}
+            _ => break,
+        }
+    }
+
+    if number.chars().any(|c| c.is_ascii_digit()) {
+        Some(number)
+    } else {
+        None
+    }
+}
+
+pub(crate) fn parse_f64(value: &str) -> Option<f64> {
+    numeric_prefix(value)?.parse().ok()
+}
+
+pub(crate) fn parse_i64(value: &str) -> Option<i64> {
+    let number = numeric_prefix(value)?;
+    match number.parse::<i64>() {
+        Ok(x) => Some(x),
+        // Decimal values, e.g. "128000.5" bit rates, are rounded.
+        Err(_) => number
+            .parse::<f64>()
+            .ok()
+            .filter(|x| x.is_finite() && x.abs() < i64::MAX as f64)
+            .map(|x| x.round() as i64),
+    }
+}
+
+pub(crate) fn parse_u64(value: &str) -> Option<u64> {
+    parse_i64(value).and_then(|x| u64::try_from(x).ok())
+}
+
+/// The separator of a field holding several values. The `*_List`
+/// summaries are always joined with " / " and the `CodecID_Compatible`
+/// brands with "/"; any other field filled more than once uses the
+/// configured `TagSeparator`. libmediainfo stores the `MultipleValues`
+/// option but no parser reads it, so it does not change the separator.
+pub(crate) fn list_separator(field: &str, tag_separator: &str) -> String {
+    if field.ends_with("_List") {
+        DEFAULT_SEPARATOR.to_string()
+    } else if field == "CodecID_Compatible" {
+        "/".to_string()
+    } else {
+        tag_separator.to_string()
+    }
+}
+
+/// An entry of a multi-value field.
+pub(crate) trait ListItem {
+    fn from_item(item: &str) -> Self;
+}
+
+impl ListItem for String {
+    fn from_item(item: &str) -> String {
+        item.to_string()
+    }
+}
+
+/// Splits a multi-value field. Empty entries are kept so that positions
+/// line up with the streams or layers they describe: "eng /  / fre" has
+/// three entries.
+pub(crate) fn split_values(value: &str, separator: &str) -> Vec<String> {
+    if value.trim().is_empty() {
+        return Vec::new();
+    }
+    value
+        .split(separator)
+        .map(|entry| entry.trim().to_string())
+        .collect()
+}
+
+/// A millisecond value ("5568", "41.708333") or one of the human readable
+/// duration renderings. Sub-millisecond digits are kept down to nanoseconds.
+pub(crate) fn parse_duration(value: &str) -> Option<Duration> {
+    let value = first_value(value);
+
+    if value.contains(':') {
+        return parse_clock_duration(value);
+    }
+    if value.chars().any(|c| c.is_ascii_alphabetic()) {
+        return parse_human_duration(value);
+    }
+
+    parse_decimal_duration(&numeric_prefix(value)?, 1_000_000)
+}
+
+// "5568.000" milliseconds -> 5.568 s, exactly. `unit_nanos` is the number of
+// nanoseconds in one unit of `number`.
+fn parse_decimal_duration(number: &str, unit_nanos: u64) -> Option<Duration> {
+    if number.starts_with('-') {
+        return None;
+    }
+    let number = number.trim_start_matches('+');
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().ok()?
+    };
+    let digits = unit_nanos.ilog10() as usize;
+    let mut fraction: String = fraction.chars().take(digits).collect();
+    while fraction.len() < digits {
+        fraction.push('0');
+    }
+    let fraction: u64 = if digits == 0 {
+        0
+    } else {
+        fraction.parse().ok()?
+    };
+
+    let nanos = whole.checked_mul(unit_nanos)?.checked_add(fraction)?;
+    Some(Duration::from_nanos(nanos))
+}
+
+// "01:32:48.123" (Duration/String3).
+fn parse_clock_duration(value: &str) -> Option<Duration> {
+    let parts: Vec<&str> = value.split(':').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+
+    let hours: u64 = parts[0].trim().parse().ok()?;
+    let minutes: u64 = parts[1].parse().ok()?;
+    let seconds = parse_decimal_duration(parts[2], 1_000_000_000)?;
+
+    Some(Duration::from_secs(hours * 3600 + minutes * 60) + seconds)
+}
+
+// "1 h 32 min", "5 s 568 ms", "41 min 34 s" (Duration/String).
+fn parse_human_duration(value: &str) -> Option<Duration> {
+    let mut total = Duration::ZERO;
+    let mut tokens = value.split_whitespace();
+    let mut matched = false;
+
+    while let Some(number) = tokens.next() {
+        let unit = tokens.next()?;
+        let unit_nanos = match unit {
+            "h" => 3_600_000_000_000,
+            "min" => 60_000_000_000,
+            "s" => 1_000_000_000,
+            "ms" => 1_000_000,
+            "µs" | "us" => 1_000,
+            "ns" => 1,
+            _ => return None,
+        };
+        total += parse_decimal_duration(number, unit_nanos)?;
+        matched = true;
+    }
+
+    matched.then_some(total)
+}
+
+/// A proportion as a fraction of one: "0.25", "25%" and "25 %" all give 0.25.
+pub(crate) fn parse_percentage(value: &str) -> Option<f64> {
+    let number = parse_f64(value)?;
+    if first_value(value).ends_with('%') {
+        Some(number / 100.0)
+    } else {
+        Some(number)
+    }
+}
+
+/// A ratio written as a decimal ("1.778") or as "16:9".
+pub(crate) fn parse_ratio(value: &str) -> Option<f64> {
+    let value = first_value(value);
+    match value.split_once(':') {
+        Some((left, right)) => {
+            let right = parse_f64(right)?;
+            if right == 0.0 {
+                return None;
+            }
+            Some(parse_f64(left)? / right)
+        }
+        None => parse_f64(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_numbers() {
+        assert_eq!(parse_i64("551194"), Some(551194));
+        assert_eq!(parse_i64("128000.6"), Some(128001));
+        assert_eq!(parse_i64("1 234 567"), Some(1234567));
+        assert_eq!(parse_i64("48000 / 44100"), Some(48000));
+        assert_eq!(parse_i64("-23"), Some(-23));
+        assert_eq!(parse_u64("-23"), None);
+        assert_eq!(parse_f64("1000 cd/m2"), Some(1000.0));
+        assert_eq!(parse_f64("23.976"), Some(23.976));
+        assert_eq!(parse_f64("Variable"), None);
+        assert_eq!(parse_f64(""), None);
+    }
+
+    #[test]
+    fn splits_values_keeping_positions() {
+        assert_eq!(
+            split_values("English /  / French", DEFAULT_SEPARATOR),
+            ["English", "", "French"]
+        );
+        assert_eq!(
+            split_values("isom/iso2/avc1/mp41", "/"),
+            ["isom", "iso2", "avc1", "mp41"]
+        );
+        assert_eq!(split_values("AAC", DEFAULT_SEPARATOR), ["AAC"]);
+        assert!(split_values("", DEFAULT_SEPARATOR).is_empty());
+
+        assert_eq!(list_separator("Audio_Language_List", ";"), " / ");
+        assert_eq!(list_separator("CodecID_Compatible", ";"), "/");
+        assert_eq!(list_separator("Format_Profile", ";"), ";");
+    }
+
+    #[test]
+    fn parses_millisecond_durations() {
+        assert_eq!(parse_duration("5568"), Some(Duration::from_millis(5568)));
+        assert_eq!(
+            parse_duration("5568.000"),
+            Some(Duration::from_millis(5568))
+        );
+        assert_eq!(
+            parse_duration("41.708333"),
+            Some(Duration::from_nanos(41_708_333))
+        );
+        assert_eq!(parse_duration("40 ms"), Some(Duration::from_millis(40)));
+        assert_eq!(parse_duration("-40"), None);
+    }
+
+    #[test]
+    fn parses_human_readable_durations() {
+        assert_eq!(
+            parse_duration("00:00:05.568"),
+            Some(Duration::from_millis(5568))
+        );
+        assert_eq!(
+            parse_duration("1 h 32 min"),
+            Some(Duration::from_secs(5520))
+        );
+        assert_eq!(
+            parse_duration("5 s 568 ms"),
+            Some(Duration::from_millis(5568))
+        );
+        assert_eq!(parse_duration("5 parsecs"), None);
+    }
+
+    #[test]
+    fn parses_percentages_and_ratios() {
+        assert_eq!(parse_percentage("0.25"), Some(0.25));
+        assert_eq!(parse_percentage("25%"), Some(0.25));
+        assert_eq!(parse_percentage("25 %"), Some(0.25));
+        assert_eq!(parse_ratio("1.778"), Some(1.778));
+        assert_eq!(parse_ratio("16:9"), Some(16.0 / 9.0));
+        assert_eq!(parse_ratio("4:0"), None);
+    }
+}
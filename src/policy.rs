@@ -0,0 +1,729 @@
+// Delivery specifications checked against a parsed file.
+//
+// A spec lists rules per stream kind, each written as "field operator
+// value":
+//
+//     name = "HD broadcast"
+//     video = ["format == AVC", "width in [1920]", "frame_rate == 25",
+//              "bit_rate <= 20 Mb/s"]
+//     audio = ["channels == 2", "sampling_rate == 48 kHz"]
+//
+// Fields are the typed accessor names of the stream types (`frame_rate`,
+// `bit_rate`) or MediaInfo field names (`FrameRate`), and are read with the
+// same parsing as the accessors. Every rule is checked against every stream
+// of its kind.
+//
+// Operators: ==, !=, <, <=, >, >=, `in [a, b]`, `not in [a, b]`,
+// `contains x`, `exists` and `missing`. Numbers may carry an SI prefix and a
+// unit ("20 Mb/s", "48 kHz"); durations are compared in seconds and accept
+// "ms", "s", "min" and "h". Dates are compared at the precision of the less
+// precise side ("recorded_date >= 2010") and languages as normalised tags
+// ("language == eng" matches "en"). Values that cannot be read as the
+// field's type are rejected when the spec is parsed.
+
+use crate::date::MediaDate;
+use crate::ffi::{MediaInfoError, MediaInfoResult, MediaInfoStream};
+use crate::frame_rate::FrameRate;
+use crate::language::LanguageTag;
+use crate::parsing::{self, ListItem};
+use crate::streams::BaseStream;
+
+use chrono::{Datelike, Timelike};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+include!(concat!(env!("OUT_DIR"), "/stream_fields.rs"));
+
+// Policies are built from a spec with `from_json` / `from_toml` only, so
+// that every rule has been checked.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Policy {
+    pub name: Option<String>,
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Rule {
+    pub stream_kind: MediaInfoStream,
+    /// The field as written in the spec.
+    pub field: String,
+    pub condition: Condition,
+    // The MediaInfo field name and value type.
+    field_name: String,
+    value_type: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Condition {
+    Equal(String),
+    NotEqual(String),
+    Less(String),
+    LessOrEqual(String),
+    Greater(String),
+    GreaterOrEqual(String),
+    In(Vec<String>),
+    NotIn(Vec<String>),
+    Contains(String),
+    Exists,
+    Missing,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RuleResult {
+    pub stream_kind: MediaInfoStream,
+    /// `None` when the file has no stream of that kind.
+    pub stream_index: Option<usize>,
+    /// The rule as "field operator value".
+    pub rule: String,
+    pub actual: Option<String>,
+    pub passed: bool,
+    /// Why the rule failed, when the value alone does not say.
+    pub message: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PolicyReport {
+    pub policy: Option<String>,
+    pub passed: bool,
+    pub results: Vec<RuleResult>,
+}
+
+// The spec as written, before its rules are parsed.
+#[derive(Deserialize)]
+struct Spec {
+    name: Option<String>,
+    #[serde(default)]
+    general: Vec<String>,
+    #[serde(default)]
+    video: Vec<String>,
+    #[serde(default)]
+    audio: Vec<String>,
+    #[serde(default)]
+    text: Vec<String>,
+    #[serde(default)]
+    other: Vec<String>,
+    #[serde(default)]
+    image: Vec<String>,
+    #[serde(default)]
+    menu: Vec<String>,
+}
+
+// A typed value read from a stream.
+enum Value {
+    Number(f64),
+    Rate(FrameRate),
+    Date(MediaDate),
+    Language(LanguageTag),
+    Text(String),
+    List(Vec<Value>),
+}
+
+impl Policy {
+    pub fn from_json(spec: &str) -> MediaInfoResult<Policy> {
+        let spec: Spec =
+            serde_json::from_str(spec).map_err(|e| MediaInfoError::InvalidPolicy(e.to_string()))?;
+        Policy::from_spec(spec)
+    }
+
+    pub fn from_toml(spec: &str) -> MediaInfoResult<Policy> {
+        let spec: Spec =
+            toml::from_str(spec).map_err(|e| MediaInfoError::InvalidPolicy(e.to_string()))?;
+        Policy::from_spec(spec)
+    }
+
+    fn from_spec(spec: Spec) -> MediaInfoResult<Policy> {
+        let kinds = [
+            (MediaInfoStream::General, spec.general),
+            (MediaInfoStream::Video, spec.video),
+            (MediaInfoStream::Audio, spec.audio),
+            (MediaInfoStream::Text, spec.text),
+            (MediaInfoStream::Other, spec.other),
+            (MediaInfoStream::Image, spec.image),
+            (MediaInfoStream::Menu, spec.menu),
+        ];
+
+        let mut rules = Vec::new();
+        for (kind, lines) in kinds {
+            for line in lines {
+                rules.push(Rule::parse(kind, &line)?);
+            }
+        }
+
+        Ok(Policy {
+            name: spec.name,
+            rules,
+        })
+    }
+
+    /// Checks every rule against every stream `streams` returns for its
+    /// kind.
+    pub(crate) fn check<'a>(
+        &self,
+        streams: impl Fn(MediaInfoStream) -> Vec<&'a dyn BaseStream>,
+    ) -> PolicyReport {
+        let mut results = Vec::new();
+        for rule in &self.rules {
+            let targets = streams(rule.stream_kind);
+            if targets.is_empty() {
+                results.push(RuleResult {
+                    stream_kind: rule.stream_kind,
+                    stream_index: None,
+                    rule: rule.to_string(),
+                    actual: None,
+                    passed: rule.condition == Condition::Missing,
+                    message: Some(format!("no {} stream", rule.stream_kind.name())),
+                });
+                continue;
+            }
+            results.extend(targets.into_iter().map(|stream| rule.check(stream)));
+        }
+
+        PolicyReport {
+            policy: self.name.clone(),
+            passed: results.iter().all(|r| r.passed),
+            results,
+        }
+    }
+}
+
+impl Rule {
+    /// Parses "field operator value" for a stream kind.
+    pub fn parse(stream_kind: MediaInfoStream, rule: &str) -> MediaInfoResult<Rule> {
+        let invalid = |why: &str| MediaInfoError::InvalidPolicy(format!("{rule:?}: {why}"));
+
+        let rule = rule.trim();
+        let end = rule
+            .find(|c: char| c.is_whitespace() || "=!<>".contains(c))
+            .unwrap_or(rule.len());
+        let (field, rest) = rule.split_at(end);
+        if field.is_empty() {
+            return Err(invalid("missing field"));
+        }
+        let condition =
+            Condition::parse(rest.trim()).ok_or_else(|| invalid("invalid condition"))?;
+
+        let (field_name, value_type) =
+            lookup_field(stream_kind, field).ok_or_else(|| invalid("unknown field"))?;
+        // `Language` holds a code, unlike the other text fields.
+        let value_type = match field_name {
+            "Language" => "language",
+            _ => value_type,
+        };
+
+        let operand_type = match value_type {
+            "list" if field_name.ends_with("_Language_List") => "language",
+            _ => value_type,
+        };
+        if let Some(operand) = condition
+            .operands()
+            .into_iter()
+            .find(|x| !is_valid_operand(x, operand_type))
+        {
+            return Err(invalid(&format!(
+                "{operand:?} is not a valid {operand_type}"
+            )));
+        }
+
+        Ok(Rule {
+            stream_kind,
+            field: field.to_string(),
+            condition,
+            field_name: field_name.to_string(),
+            value_type: value_type.to_string(),
+        })
+    }
+
+    fn check(&self, stream: &dyn BaseStream) -> RuleResult {
+        let result = |actual: Option<String>, passed: bool, message: Option<String>| RuleResult {
+            stream_kind: self.stream_kind,
+            stream_index: Some(stream.index()),
+            rule: self.to_string(),
+            actual,
+            passed,
+            message,
+        };
+
+        let raw = stream
+            .field(&self.field_name)
+            .ok()
+            .filter(|x| !x.is_empty());
+        let Some(raw) = raw else {
+            let passed = self.condition == Condition::Missing;
+            return result(
+                None,
+                passed,
+                (!passed).then(|| "field is missing".to_string()),
+            );
+        };
+
+        let Some(value) = self.read(stream, &raw) else {
+            return result(
+                Some(raw),
+                false,
+                Some(format!("cannot read the value as {}", self.value_type)),
+            );
+        };
+        match self.condition.evaluate(&value, &self.value_type) {
+            Ok(passed) => result(Some(raw), passed, None),
+            Err(message) => result(Some(raw), false, Some(message)),
+        }
+    }
+
+    // The value with the parsing of the matching `mediainfo_*` accessor.
+    fn read(&self, stream: &dyn BaseStream, raw: &str) -> Option<Value> {
+        let value = || Ok(raw.to_string());
+        Some(match self.value_type.as_str() {
+            "i64" => Value::Number(stream.result_to_i64(value()).ok()? as f64),
+            "f64" => Value::Number(stream.result_to_f64(value()).ok()?),
+            "percentage" => Value::Number(stream.result_to_percentage(value()).ok()?),
+            "ratio" => Value::Number(stream.result_to_ratio(value()).ok()?),
+            "duration" => Value::Number(stream.result_to_duration(value()).ok()?.as_secs_f64()),
+            "frame_rate" => Value::Rate(stream.frame_rate_field(&self.field_name).ok()?),
+            "date" => Value::Date(stream.result_to_date(value()).ok()?),
+            "language" => Value::Language(LanguageTag::parse(raw)?),
+            "list" => {
+                let languages = self.field_name.ends_with("_Language_List");
+                let separator = stream.list_separator(&self.field_name);
+                let items = parsing::split_values(raw, &separator).into_iter();
+                Value::List(match languages {
+                    true => items
+                        .map(|x| Value::Language(LanguageTag::from_item(&x)))
+                        .collect(),
+                    false => items.map(Value::Text).collect(),
+                })
+            }
+            _ => Value::Text(raw.to_string()),
+        })
+    }
+}
+
+impl Condition {
+    fn parse(condition: &str) -> Option<Condition> {
+        let operand = |rest: &str| Some(unquote(rest.trim()).to_string()).filter(|x| !x.is_empty());
+        let list = |rest: &str| {
+            let items = rest.trim().strip_prefix('[')?.strip_suffix(']')?;
+            Some(
+                items
+                    .split(',')
+                    .map(|x| unquote(x.trim()).to_string())
+                    .filter(|x| !x.is_empty())
+                    .collect(),
+            )
+        };
+
+        let symbolic = [
+            ("==", Condition::Equal as fn(String) -> Condition),
+            ("!=", Condition::NotEqual),
+            ("<=", Condition::LessOrEqual),
+            (">=", Condition::GreaterOrEqual),
+            ("<", Condition::Less),
+            (">", Condition::Greater),
+        ];
+        for (operator, condition_for) in symbolic {
+            if let Some(rest) = condition.strip_prefix(operator) {
+                return operand(rest).map(condition_for);
+            }
+        }
+
+        let (word, rest) = condition
+            .split_once(char::is_whitespace)
+            .unwrap_or((condition, ""));
+        match word {
+            "exists" if rest.trim().is_empty() => Some(Condition::Exists),
+            "missing" if rest.trim().is_empty() => Some(Condition::Missing),
+            "contains" => operand(rest).map(Condition::Contains),
+            "in" => list(rest).map(Condition::In),
+            "not" => list(rest.trim().strip_prefix("in")?).map(Condition::NotIn),
+            _ => None,
+        }
+    }
+
+    fn evaluate(&self, value: &Value, value_type: &str) -> Result<bool, String> {
+        let compare = |expected: &str| compare(value, expected, value_type);
+        let equal = |expected: &str| compare(expected).map(|x| x == Ordering::Equal);
+
+        match self {
+            Condition::Exists => Ok(true),
+            Condition::Missing => Ok(false),
+            Condition::Equal(x) => equal(x),
+            Condition::NotEqual(x) => equal(x).map(|x| !x),
+            Condition::Less(x) => compare(x).map(Ordering::is_lt),
+            Condition::LessOrEqual(x) => compare(x).map(Ordering::is_le),
+            Condition::Greater(x) => compare(x).map(Ordering::is_gt),
+            Condition::GreaterOrEqual(x) => compare(x).map(Ordering::is_ge),
+            Condition::In(items) => any(items, equal),
+            Condition::NotIn(items) => any(items, equal).map(|x| !x),
+            Condition::Contains(x) => Ok(match value {
+                Value::List(items) => items
+                    .iter()
+                    .any(|item| self::compare(item, x, value_type) == Ok(Ordering::Equal)),
+                Value::Text(text) => text.contains(x.as_str()),
+                _ => equal(x)?,
+            }),
+        }
+    }
+
+    // The values written in the condition.
+    fn operands(&self) -> Vec<&String> {
+        match self {
+            Condition::Equal(x)
+            | Condition::NotEqual(x)
+            | Condition::Less(x)
+            | Condition::LessOrEqual(x)
+            | Condition::Greater(x)
+            | Condition::GreaterOrEqual(x)
+            | Condition::Contains(x) => vec![x],
+            Condition::In(items) | Condition::NotIn(items) => items.iter().collect(),
+            Condition::Exists | Condition::Missing => Vec::new(),
+        }
+    }
+}
+
+// Whether `operand` can be compared with a value of `value_type`.
+fn is_valid_operand(operand: &str, value_type: &str) -> bool {
+    match value_type {
+        "i64" | "f64" | "percentage" | "ratio" | "duration" => {
+            parse_number(operand, value_type).is_some()
+        }
+        "frame_rate" => parse_frame_rate(operand).is_some(),
+        "date" => MediaDate::parse(operand).is_some(),
+        "language" => LanguageTag::parse(operand).is_some(),
+        _ => true,
+    }
+}
+
+fn any(items: &[String], equal: impl Fn(&str) -> Result<bool, String>) -> Result<bool, String> {
+    for item in items {
+        if equal(item)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn compare(value: &Value, expected: &str, value_type: &str) -> Result<Ordering, String> {
+    let not_a_number = || format!("{expected:?} is not a number");
+    match value {
+        Value::Number(actual) => {
+            let expected = parse_number(expected, value_type).ok_or_else(not_a_number)?;
+            let tolerance = 1e-9 * expected.abs().max(1.0);
+            Ok(if (actual - expected).abs() <= tolerance {
+                Ordering::Equal
+            } else {
+                actual.total_cmp(&expected)
+            })
+        }
+        Value::Rate(actual) => {
+            let expected = parse_frame_rate(expected).ok_or_else(not_a_number)?;
+            Ok(actual.cmp(&expected))
+        }
+        Value::Date(actual) => {
+            let expected =
+                MediaDate::parse(expected).ok_or_else(|| format!("{expected:?} is not a date"))?;
+            Ok(compare_dates(actual, &expected))
+        }
+        Value::Language(actual) => {
+            let expected = LanguageTag::parse(expected)
+                .ok_or_else(|| format!("{expected:?} is not a language"))?;
+            Ok(actual.as_str().cmp(expected.as_str()))
+        }
+        Value::Text(actual) => Ok(actual.as_str().cmp(expected)),
+        Value::List(items) => {
+            let items: Vec<&str> = items
+                .iter()
+                .map(|item| match item {
+                    Value::Language(x) => x.original(),
+                    Value::Text(x) => x.as_str(),
+                    _ => "",
+                })
+                .collect();
+            Ok(items.join(" / ").as_str().cmp(expected))
+        }
+    }
+}
+
+// Orders two dates on the parts both have, so "2010" equals any day of
+// 2010. Timestamps with a zone are compared in UTC.
+fn compare_dates(actual: &MediaDate, expected: &MediaDate) -> Ordering {
+    let parts = |date: &MediaDate| -> Vec<i64> {
+        let naive = match date {
+            MediaDate::DateTime(x) => Some(x.naive_utc()),
+            MediaDate::Local(x) => Some(*x),
+            _ => None,
+        };
+        match (naive, date) {
+            (Some(x), _) => vec![
+                x.year() as i64,
+                x.month() as i64,
+                x.day() as i64,
+                x.num_seconds_from_midnight() as i64,
+                x.nanosecond() as i64,
+            ],
+            (None, MediaDate::Date(x)) => vec![x.year() as i64, x.month() as i64, x.day() as i64],
+            (None, MediaDate::YearMonth { year, month }) => vec![*year as i64, *month as i64],
+            (None, _) => vec![date.year() as i64],
+        }
+    };
+    let (actual, expected) = (parts(actual), parts(expected));
+    let len = actual.len().min(expected.len());
+    actual[..len].cmp(&expected[..len])
+}
+
+// A number in the unit of `value_type`: seconds for durations, a fraction
+// of one for percentages ("25 %"), "16:9" or a decimal for ratios.
+fn parse_number(value: &str, value_type: &str) -> Option<f64> {
+    match value_type {
+        "duration" => parse_quantity(value, true),
+        "ratio" if value.contains(':') => parsing::parse_ratio(value),
+        _ => parse_quantity(value, false),
+    }
+}
+
+// "20 Mb/s" -> 20e6, "48 kHz" -> 48000, "90 min" -> 5400 for durations.
+fn parse_quantity(value: &str, duration: bool) -> Option<f64> {
+    let value = value.trim();
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+'))
+        .unwrap_or(value.len());
+    let number: f64 = value[..end].parse().ok()?;
+    let unit = value[end..].trim();
+
+    if duration {
+        let scale = match unit {
+            "" | "s" => 1.0,
+            "ms" => 0.001,
+            "min" => 60.0,
+            "h" => 3600.0,
+            _ => return None,
+        };
+        return Some(number * scale);
+    }
+
+    if unit == "%" {
+        return Some(number / 100.0);
+    }
+    let (scale, base) = match unit.split_at(unit.chars().next().map_or(0, char::len_utf8)) {
+        ("k" | "K", base) if UNITS.contains(&base) => (1e3, base),
+        ("M", base) if UNITS.contains(&base) => (1e6, base),
+        ("G", base) if UNITS.contains(&base) => (1e9, base),
+        _ => (1.0, unit),
+    };
+    (base.is_empty() || UNITS.contains(&base)).then_some(number * scale)
+}
+
+// The units MediaInfo prints after numbers, accepted after a value with or
+// without an SI prefix.
+const UNITS: &[&str] = &[
+    "b/s", "bps", "bit/s", "B/s", "Hz", "B", "byte", "bytes", "bit", "bits", "px", "pixel",
+    "pixels", "fps", "ch", "channel", "channels", "cd/m2",
+];
+
+// "25", "29.97" or "30000/1001".
+fn parse_frame_rate(value: &str) -> Option<FrameRate> {
+    let value = value.trim().trim_end_matches("fps").trim();
+    match value.split_once('/') {
+        Some((num, den)) => FrameRate::new(num.trim().parse().ok()?, den.trim().parse().ok()?),
+        None => FrameRate::from_decimal(value),
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|x| x.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+// (field name, value type) for an accessor or field name.
+fn lookup_field(stream_kind: MediaInfoStream, field: &str) -> Option<(&'static str, &'static str)> {
+    let fields = || {
+        STREAM_FIELDS
+            .iter()
+            .filter(|(kind, ..)| *kind == stream_kind.name())
+    };
+    fields()
+        .find(|(_, accessor, ..)| *accessor == field)
+        .or_else(|| fields().find(|(_, _, name, ..)| *name == field))
+        .map(|(_, _, name, value_type)| (*name, *value_type))
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.field, self.condition)
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Equal(x) => write!(f, "== {x}"),
+            Condition::NotEqual(x) => write!(f, "!= {x}"),
+            Condition::Less(x) => write!(f, "< {x}"),
+            Condition::LessOrEqual(x) => write!(f, "<= {x}"),
+            Condition::Greater(x) => write!(f, "> {x}"),
+            Condition::GreaterOrEqual(x) => write!(f, ">= {x}"),
+            Condition::In(x) => write!(f, "in [{}]", x.join(", ")),
+            Condition::NotIn(x) => write!(f, "not in [{}]", x.join(", ")),
+            Condition::Contains(x) => write!(f, "contains {x}"),
+            Condition::Exists => f.write_str("exists"),
+            Condition::Missing => f.write_str("missing"),
+        }
+    }
+}
+
+impl PolicyReport {
+    pub fn failures(&self) -> impl Iterator<Item = &RuleResult> {
+        self.results.iter().filter(|r| !r.passed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::{AudioSnapshot, VideoSnapshot};
+
+    fn check(policy: &Policy, video: &[VideoSnapshot], audio: &[AudioSnapshot]) -> PolicyReport {
+        policy.check(|kind| match kind {
+            MediaInfoStream::Video => video.iter().map(|x| x as &dyn BaseStream).collect(),
+            MediaInfoStream::Audio => audio.iter().map(|x| x as &dyn BaseStream).collect(),
+            _ => Vec::new(),
+        })
+    }
+
+    const SPEC: &str = r#"
+        name = "HD broadcast"
+        video = [
+            "format == AVC",
+            "width in [1920]",
+            "frame_rate == 25",
+            "bit_rate <= 20 Mb/s",
+            "duration >= 30 min",
+        ]
+        audio = ["channels == 2", "SamplingRate == 48 kHz", "language not in [und]"]
+    "#;
+
+    #[test]
+    fn checks_every_rule_against_every_stream() {
+        let policy = Policy::from_toml(SPEC).unwrap();
+        assert_eq!(policy.rules.len(), 8);
+
+        let report = check(
+            &policy,
+            &[VideoSnapshot::from_pairs(
+                0,
+                &[
+                    ("Format", "AVC"),
+                    ("Width", "1920"),
+                    ("FrameRate", "25.000"),
+                    ("BitRate", "18500000"),
+                    ("Duration", "1800000.000"),
+                ],
+            )],
+            &[
+                AudioSnapshot::from_pairs(
+                    0,
+                    &[
+                        ("Channel(s)", "2"),
+                        ("SamplingRate", "48000"),
+                        ("Language", "en"),
+                    ],
+                ),
+                AudioSnapshot::from_pairs(1, &[("Channel(s)", "6"), ("SamplingRate", "48000")]),
+            ],
+        );
+
+        assert!(!report.passed);
+        assert_eq!(report.policy.as_deref(), Some("HD broadcast"));
+        assert_eq!(report.results.len(), 11);
+
+        let failures: Vec<_> = report.failures().collect();
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].rule, "channels == 2");
+        assert_eq!(failures[0].stream_index, Some(1));
+        assert_eq!(failures[0].actual.as_deref(), Some("6"));
+        assert_eq!(failures[1].rule, "language not in [und]");
+        assert_eq!(failures[1].message.as_deref(), Some("field is missing"));
+    }
+
+    #[test]
+    fn reports_missing_streams_and_bad_values() {
+        let policy = Policy::from_json(
+            r#"{"video": ["frame_rate == 30000/1001", "width > 1280"], "audio": ["format exists"]}"#,
+        )
+        .unwrap();
+
+        let report = check(
+            &policy,
+            &[VideoSnapshot::from_pairs(
+                0,
+                &[("FrameRate", "29.970"), ("Width", "wide")],
+            )],
+            &[],
+        );
+        let results = &report.results;
+        assert!(results[0].passed);
+        assert!(!results[1].passed);
+        assert_eq!(
+            results[1].message.as_deref(),
+            Some("cannot read the value as i64")
+        );
+        assert_eq!(results[2].stream_index, None);
+        assert_eq!(results[2].message.as_deref(), Some("no Audio stream"));
+    }
+
+    #[test]
+    fn compares_languages_and_dates_as_such() {
+        let policy = Policy::from_toml(
+            r#"
+            video = ["encoded_date >= 2010", "encoded_date < 2010-03-21"]
+            audio = ["language == eng", "language in [fre, ger]"]
+            "#,
+        )
+        .unwrap();
+
+        let report = check(
+            &policy,
+            &[VideoSnapshot::from_pairs(
+                0,
+                &[("Encoded_Date", "2010-03-20 21:29:12 UTC")],
+            )],
+            &[AudioSnapshot::from_pairs(0, &[("Language", "en")])],
+        );
+        let passed: Vec<bool> = report.results.iter().map(|x| x.passed).collect();
+        assert_eq!(passed, [true, true, true, false]);
+    }
+
+    #[test]
+    fn rejects_invalid_specs() {
+        for rule in [
+            "bit_rate <= 20 foo",
+            "duration > 3 days",
+            "frame_rate == fast",
+            "width in [1920, wide]",
+        ] {
+            assert!(
+                matches!(
+                    Rule::parse(MediaInfoStream::Video, rule),
+                    Err(MediaInfoError::InvalidPolicy(_))
+                ),
+                "{rule}"
+            );
+        }
+        assert!(Rule::parse(MediaInfoStream::Audio, "language == act").is_err());
+        assert!(Rule::parse(MediaInfoStream::Video, "bit_rate <= 20 Mb/s").is_ok());
+
+        assert!(matches!(
+            Policy::from_toml(r#"video = ["colour == red"]"#),
+            Err(MediaInfoError::InvalidPolicy(_))
+        ));
+        assert!(Policy::from_toml(r#"video = ["width ~ 1920"]"#).is_err());
+        assert!(Policy::from_json(r#"{"video": "width == 1920"}"#).is_err());
+        assert_eq!(
+            Rule::parse(MediaInfoStream::Video, "width not in [720, 1280]")
+                .unwrap()
+                .condition,
+            Condition::NotIn(vec!["720".to_string(), "1280".to_string()])
+        );
+    }
+}
@@ -0,0 +1,193 @@
+// Programs (services) of MPEG transport streams.
+//
+// MediaInfo reports each program as a menu stream: `ID` is the PMT PID,
+// `MenuID` the program number and the DVB/ATSC service descriptors fill
+// `ServiceName`, `ServiceProvider` and `ServiceType`. The elementary streams
+// are listed positionally in `List` (their PIDs, "PID-page" for teletext
+// pages), `List_StreamKind` and `List_StreamPos`, which point at the tracks
+// MediaInfo created for them.
+
+use crate::ffi::MediaInfoStream;
+use crate::parsing;
+use crate::streams::{AudioStream, ImageStream, OtherStream, TextStream, VideoStream};
+
+#[derive(Clone, Debug)]
+pub enum ProgramTrack {
+    Video(VideoStream),
+    Audio(AudioStream),
+    Text(TextStream),
+    Other(OtherStream),
+    Image(ImageStream),
+}
+
+#[derive(Clone, Debug)]
+pub struct ProgramStream {
+    /// The entry of `List`, e.g. "2064" or "2068-888" for a teletext page.
+    pub id: String,
+    pub pid: Option<u64>,
+    pub format: Option<String>,
+    pub kind: Option<MediaInfoStream>,
+    /// The track MediaInfo created for this stream, if any.
+    pub track: Option<ProgramTrack>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Program {
+    pub pmt_pid: Option<u64>,
+    pub program_number: Option<u64>,
+    pub service_name: Option<String>,
+    pub service_provider: Option<String>,
+    pub service_type: Option<String>,
+    pub streams: Vec<ProgramStream>,
+}
+
+impl Program {
+    /// Builds a program from the fields of a menu stream. `resolve` maps a
+    /// stream kind and position to the matching track. `None` when the menu
+    /// lists no elementary streams and has no program number, i.e. is not
+    /// a program.
+    pub(crate) fn from_fields(
+        get: impl Fn(&str) -> Option<String>,
+        resolve: impl Fn(MediaInfoStream, usize) -> Option<ProgramTrack>,
+    ) -> Option<Program> {
+        let list = |name: &str| {
+            get(name)
+                .map(|x| parsing::split_values(&x, parsing::DEFAULT_SEPARATOR))
+                .unwrap_or_default()
+        };
+        let text = |name: &str| get(name).filter(|x| !x.is_empty());
+
+        let ids = list("List");
+        let program_number = text("MenuID").and_then(|x| parsing::parse_u64(&x));
+        if ids.is_empty() && program_number.is_none() {
+            return None;
+        }
+
+        let kinds = list("List_StreamKind");
+        let positions = list("List_StreamPos");
+        let formats = list("Format");
+
+        let streams = ids
+            .into_iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let kind = kinds.get(i).and_then(|x| stream_kind(x));
+                let position = positions.get(i).and_then(|x| x.trim().parse().ok());
+                ProgramStream {
+                    pid: id.split('-').next().and_then(parsing::parse_u64),
+                    format: formats.get(i).filter(|x| !x.is_empty()).cloned(),
+                    kind,
+                    track: kind.zip(position).and_then(|(k, p)| resolve(k, p)),
+                    id,
+                }
+            })
+            .collect();
+
+        Some(Program {
+            pmt_pid: text("ID").and_then(|x| parsing::parse_u64(&x)),
+            program_number,
+            service_name: text("ServiceName"),
+            service_provider: text("ServiceProvider"),
+            service_type: text("ServiceType"),
+            streams,
+        })
+    }
+
+    pub fn video_streams(&self) -> Vec<&VideoStream> {
+        self.tracks()
+            .filter_map(|x| match x {
+                ProgramTrack::Video(x) => Some(x),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn audio_streams(&self) -> Vec<&AudioStream> {
+        self.tracks()
+            .filter_map(|x| match x {
+                ProgramTrack::Audio(x) => Some(x),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn text_streams(&self) -> Vec<&TextStream> {
+        self.tracks()
+            .filter_map(|x| match x {
+                ProgramTrack::Text(x) => Some(x),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Whether the program carries the elementary stream with this PID.
+    pub fn contains_pid(&self, pid: u64) -> bool {
+        self.streams.iter().any(|x| x.pid == Some(pid))
+    }
+
+    fn tracks(&self) -> impl Iterator<Item = &ProgramTrack> {
+        self.streams.iter().filter_map(|x| x.track.as_ref())
+    }
+}
+
+// `List_StreamKind` holds the numeric stream kind, e.g. "2" for audio.
+fn stream_kind(value: &str) -> Option<MediaInfoStream> {
+    match value.trim() {
+        "1" => Some(MediaInfoStream::Video),
+        "2" => Some(MediaInfoStream::Audio),
+        "3" => Some(MediaInfoStream::Text),
+        "4" => Some(MediaInfoStream::Other),
+        "5" => Some(MediaInfoStream::Image),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::MenuSnapshot;
+
+    fn program(fields: &[(&str, &str)]) -> Option<Program> {
+        let menu = MenuSnapshot::from_pairs(0, fields);
+        Program::from_fields(|name| menu.get(name).map(str::to_string), |_, _| None)
+    }
+
+    #[test]
+    fn reads_services_and_elementary_streams() {
+        let program = program(&[
+            ("ID", "256"),
+            ("MenuID", "1"),
+            ("ServiceName", "Channel One"),
+            ("ServiceProvider", "Broadcaster"),
+            ("ServiceType", "digital television"),
+            ("Format", "AVC / AC-3 / Teletext /  / "),
+            ("List", "4113 / 4352 / 4353-888 / 4354 / 8191"),
+            ("List_StreamKind", "1 / 2 / 3 /  / "),
+            ("List_StreamPos", "0 / 0 / 0 /  / "),
+        ])
+        .unwrap();
+
+        assert_eq!(program.pmt_pid, Some(256));
+        assert_eq!(program.program_number, Some(1));
+        assert_eq!(program.service_name.as_deref(), Some("Channel One"));
+        assert_eq!(program.streams.len(), 5);
+
+        let teletext = &program.streams[2];
+        assert_eq!(teletext.id, "4353-888");
+        assert_eq!(teletext.pid, Some(4353));
+        assert_eq!(teletext.kind, Some(MediaInfoStream::Text));
+        assert_eq!(teletext.format.as_deref(), Some("Teletext"));
+
+        let unknown = &program.streams[3];
+        assert_eq!(unknown.kind, None);
+        assert_eq!(unknown.format, None);
+        assert!(program.contains_pid(4113));
+        assert!(!program.contains_pid(256));
+    }
+
+    #[test]
+    fn ignores_menus_that_are_not_programs() {
+        assert!(program(&[("Chapters_Pos_Begin", "40")]).is_none());
+        assert!(program(&[("MenuID", "3")]).unwrap().streams.is_empty());
+    }
+}
@@ -0,0 +1,555 @@
+// Typed model of MediaInfo's JSON output (`Output=JSON`).
+//
+// The schema is `{"creatingLibrary": {...}, "media": {"@ref": ..., "track": [...]}}`
+// where every track carries its stream kind in `@type`, all values are
+// strings (numbers included) and non-standard fields are grouped under
+// `extra`. Numeric and Yes/No fields are parsed to proper types here; any
+// field the model does not name, or whose value does not parse, is kept in
+// `fields`.
+
+use crate::ffi::{MediaInfoError, MediaInfoResult, MediaInfoStream};
+
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaInfoReport {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creating_library: Option<CreatingLibrary>,
+    #[serde(default)]
+    pub media: Media,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreatingLibrary {
+    pub name: String,
+    pub version: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub url: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Media {
+    #[serde(rename = "@ref", default)]
+    pub reference: String,
+    #[serde(rename = "track", default)]
+    pub tracks: Vec<Track>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "@type")]
+pub enum Track {
+    General(GeneralTrack),
+    Video(VideoTrack),
+    Audio(AudioTrack),
+    Text(TextTrack),
+    Other(OtherTrack),
+    Image(ImageTrack),
+    Menu(MenuTrack),
+}
+
+impl Track {
+    pub fn kind(&self) -> MediaInfoStream {
+        match self {
+            Track::General(_) => MediaInfoStream::General,
+            Track::Video(_) => MediaInfoStream::Video,
+            Track::Audio(_) => MediaInfoStream::Audio,
+            Track::Text(_) => MediaInfoStream::Text,
+            Track::Other(_) => MediaInfoStream::Other,
+            Track::Image(_) => MediaInfoStream::Image,
+            Track::Menu(_) => MediaInfoStream::Menu,
+        }
+    }
+
+    /// Non-standard fields, e.g. container specific metadata or chapters.
+    pub fn extra(&self) -> &BTreeMap<String, Value> {
+        match self {
+            Track::General(t) => &t.extra,
+            Track::Video(t) => &t.extra,
+            Track::Audio(t) => &t.extra,
+            Track::Text(t) => &t.extra,
+            Track::Other(t) => &t.extra,
+            Track::Image(t) => &t.extra,
+            Track::Menu(t) => &t.extra,
+        }
+    }
+
+    /// Fields present in the output but not modelled by the track struct.
+    pub fn fields(&self) -> &BTreeMap<String, Value> {
+        match self {
+            Track::General(t) => &t.fields,
+            Track::Video(t) => &t.fields,
+            Track::Audio(t) => &t.fields,
+            Track::Text(t) => &t.fields,
+            Track::Other(t) => &t.fields,
+            Track::Image(t) => &t.fields,
+            Track::Menu(t) => &t.fields,
+        }
+    }
+}
+
+macro_rules! report_track {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            text { $($t_field:ident => $t_key:literal),* $(,)? }
+            number { $($n_field:ident: $n_ty:ty => $n_key:literal),* $(,)? }
+            flag { $($f_field:ident => $f_key:literal),* $(,)? }
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+        #[serde(from = "BTreeMap<String, Value>")]
+        pub struct $name {
+            /// Position among the tracks of the same kind, only present when
+            /// there is more than one.
+            #[serde(rename = "@typeorder", skip_serializing_if = "Option::is_none", serialize_with = "number")]
+            pub type_order: Option<u32>,
+            $(
+                #[serde(rename = $t_key, skip_serializing_if = "Option::is_none")]
+                pub $t_field: Option<String>,
+            )*
+            $(
+                #[serde(rename = $n_key, skip_serializing_if = "Option::is_none", serialize_with = "number")]
+                pub $n_field: Option<$n_ty>,
+            )*
+            $(
+                #[serde(rename = $f_key, skip_serializing_if = "Option::is_none", serialize_with = "flag")]
+                pub $f_field: Option<bool>,
+            )*
+            #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+            pub extra: BTreeMap<String, Value>,
+            #[serde(flatten)]
+            pub fields: BTreeMap<String, Value>,
+        }
+
+        impl From<BTreeMap<String, Value>> for $name {
+            fn from(mut fields: BTreeMap<String, Value>) -> Self {
+                $name {
+                    type_order: take(&mut fields, "@typeorder", parse_number),
+                    $($t_field: take(&mut fields, $t_key, parse_text),)*
+                    $($n_field: take(&mut fields, $n_key, parse_number),)*
+                    $($f_field: take(&mut fields, $f_key, parse_flag),)*
+                    extra: take(&mut fields, "extra", parse_object).unwrap_or_default(),
+                    fields,
+                }
+            }
+        }
+    };
+}
+
+report_track!(
+    /// Durations are in seconds, as reported by the JSON output.
+    GeneralTrack {
+        text {
+            unique_id => "UniqueID",
+            file_extension => "FileExtension",
+            format => "Format",
+            format_profile => "Format_Profile",
+            codec_id => "CodecID",
+            title => "Title",
+            movie => "Movie",
+            encoded_date => "Encoded_Date",
+            tagged_date => "Tagged_Date",
+            file_modified_date => "File_Modified_Date",
+            encoded_application => "Encoded_Application",
+            encoded_library => "Encoded_Library",
+        }
+        number {
+            video_count: u32 => "VideoCount",
+            audio_count: u32 => "AudioCount",
+            text_count: u32 => "TextCount",
+            other_count: u32 => "OtherCount",
+            image_count: u32 => "ImageCount",
+            menu_count: u32 => "MenuCount",
+            file_size: u64 => "FileSize",
+            duration: f64 => "Duration",
+            overall_bit_rate: u64 => "OverallBitRate",
+            frame_rate: f64 => "FrameRate",
+            frame_count: u64 => "FrameCount",
+            stream_size: u64 => "StreamSize",
+            header_size: u64 => "HeaderSize",
+            data_size: u64 => "DataSize",
+            footer_size: u64 => "FooterSize",
+        }
+        flag {
+            is_streamable => "IsStreamable",
+        }
+    }
+);
+
+report_track!(
+    VideoTrack {
+        text {
+            stream_order => "StreamOrder",
+            id => "ID",
+            format => "Format",
+            format_profile => "Format_Profile",
+            format_level => "Format_Level",
+            format_tier => "Format_Tier",
+            codec_id => "CodecID",
+            bit_rate_mode => "BitRate_Mode",
+            frame_rate_mode => "FrameRate_Mode",
+            color_space => "ColorSpace",
+            chroma_subsampling => "ChromaSubsampling",
+            scan_type => "ScanType",
+            title => "Title",
+            language => "Language",
+            hdr_format => "HDR_Format",
+            colour_range => "colour_range",
+            colour_primaries => "colour_primaries",
+            transfer_characteristics => "transfer_characteristics",
+            matrix_coefficients => "matrix_coefficients",
+        }
+        number {
+            duration: f64 => "Duration",
+            bit_rate: u64 => "BitRate",
+            width: u32 => "Width",
+            height: u32 => "Height",
+            sampled_width: u32 => "Sampled_Width",
+            sampled_height: u32 => "Sampled_Height",
+            pixel_aspect_ratio: f64 => "PixelAspectRatio",
+            display_aspect_ratio: f64 => "DisplayAspectRatio",
+            rotation: f64 => "Rotation",
+            frame_rate: f64 => "FrameRate",
+            frame_rate_num: u32 => "FrameRate_Num",
+            frame_rate_den: u32 => "FrameRate_Den",
+            frame_count: u64 => "FrameCount",
+            bit_depth: u32 => "BitDepth",
+            delay: f64 => "Delay",
+            stream_size: u64 => "StreamSize",
+        }
+        flag {
+            default => "Default",
+            forced => "Forced",
+        }
+    }
+);
+
+report_track!(
+    AudioTrack {
+        text {
+            stream_order => "StreamOrder",
+            id => "ID",
+            format => "Format",
+            format_commercial => "Format_Commercial_IfAny",
+            format_additional_features => "Format_AdditionalFeatures",
+            codec_id => "CodecID",
+            bit_rate_mode => "BitRate_Mode",
+            channel_positions => "ChannelPositions",
+            channel_layout => "ChannelLayout",
+            compression_mode => "Compression_Mode",
+            title => "Title",
+            language => "Language",
+        }
+        number {
+            duration: f64 => "Duration",
+            bit_rate: u64 => "BitRate",
+            channels: u32 => "Channels",
+            samples_per_frame: f64 => "SamplesPerFrame",
+            sampling_rate: u32 => "SamplingRate",
+            sampling_count: u64 => "SamplingCount",
+            frame_rate: f64 => "FrameRate",
+            frame_count: u64 => "FrameCount",
+            bit_depth: u32 => "BitDepth",
+            delay: f64 => "Delay",
+            stream_size: u64 => "StreamSize",
+        }
+        flag {
+            default => "Default",
+            forced => "Forced",
+        }
+    }
+);
+
+report_track!(
+    TextTrack {
+        text {
+            stream_order => "StreamOrder",
+            id => "ID",
+            format => "Format",
+            codec_id => "CodecID",
+            title => "Title",
+            language => "Language",
+        }
+        number {
+            duration: f64 => "Duration",
+            bit_rate: u64 => "BitRate",
+            frame_rate: f64 => "FrameRate",
+            frame_count: u64 => "FrameCount",
+            element_count: u64 => "ElementCount",
+            stream_size: u64 => "StreamSize",
+        }
+        flag {
+            default => "Default",
+            forced => "Forced",
+        }
+    }
+);
+
+report_track!(
+    OtherTrack {
+        text {
+            stream_order => "StreamOrder",
+            id => "ID",
+            kind => "Type",
+            format => "Format",
+            time_code_first_frame => "TimeCode_FirstFrame",
+            title => "Title",
+            language => "Language",
+        }
+        number {
+            duration: f64 => "Duration",
+            frame_rate: f64 => "FrameRate",
+            frame_count: u64 => "FrameCount",
+        }
+        flag {}
+    }
+);
+
+report_track!(
+    ImageTrack {
+        text {
+            format => "Format",
+            codec_id => "CodecID",
+            color_space => "ColorSpace",
+            chroma_subsampling => "ChromaSubsampling",
+            compression_mode => "Compression_Mode",
+            title => "Title",
+        }
+        number {
+            width: u32 => "Width",
+            height: u32 => "Height",
+            bit_depth: u32 => "BitDepth",
+            stream_size: u64 => "StreamSize",
+        }
+        flag {}
+    }
+);
+
+report_track!(
+    /// Chapters are reported in `extra`, keyed by their start time.
+    MenuTrack {
+        text {
+            stream_order => "StreamOrder",
+            id => "ID",
+            format => "Format",
+            codec_id => "CodecID",
+            language => "Language",
+        }
+        number {
+            duration: f64 => "Duration",
+            delay: f64 => "Delay",
+        }
+        flag {}
+    }
+);
+
+impl MediaInfoReport {
+    /// Parses the output of `Option("Output", "JSON")` followed by `Inform()`.
+    pub fn from_json(json: &str) -> MediaInfoResult<MediaInfoReport> {
+        serde_json::from_str(json).map_err(|e| MediaInfoError::InvalidReport(e.to_string()))
+    }
+
+    pub fn to_json(&self) -> MediaInfoResult<String> {
+        serde_json::to_string(self).map_err(|e| MediaInfoError::InvalidReport(e.to_string()))
+    }
+
+    pub fn tracks(&self) -> &[Track] {
+        &self.media.tracks
+    }
+
+    pub fn general(&self) -> Option<&GeneralTrack> {
+        self.tracks().iter().find_map(|t| match t {
+            Track::General(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    pub fn video(&self) -> impl Iterator<Item = &VideoTrack> {
+        self.tracks().iter().filter_map(|t| match t {
+            Track::Video(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    pub fn audio(&self) -> impl Iterator<Item = &AudioTrack> {
+        self.tracks().iter().filter_map(|t| match t {
+            Track::Audio(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    pub fn text(&self) -> impl Iterator<Item = &TextTrack> {
+        self.tracks().iter().filter_map(|t| match t {
+            Track::Text(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    pub fn other(&self) -> impl Iterator<Item = &OtherTrack> {
+        self.tracks().iter().filter_map(|t| match t {
+            Track::Other(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    pub fn images(&self) -> impl Iterator<Item = &ImageTrack> {
+        self.tracks().iter().filter_map(|t| match t {
+            Track::Image(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    pub fn menus(&self) -> impl Iterator<Item = &MenuTrack> {
+        self.tracks().iter().filter_map(|t| match t {
+            Track::Menu(t) => Some(t),
+            _ => None,
+        })
+    }
+}
+
+// MediaInfo writes numbers and Yes/No flags as JSON strings. A value that
+// does not convert, such as "bogus" or the multiple values of
+// "48000 / 24000", stays as it was in `fields` instead of being dropped.
+fn take<T>(
+    fields: &mut BTreeMap<String, Value>,
+    key: &str,
+    convert: fn(&Value) -> Option<T>,
+) -> Option<T> {
+    let converted = convert(fields.get(key)?)?;
+    fields.remove(key);
+    Some(converted)
+}
+
+fn parse_text(value: &Value) -> Option<String> {
+    value.as_str().map(str::to_string)
+}
+
+fn parse_number<T: FromStr>(value: &Value) -> Option<T> {
+    match value {
+        Value::String(s) => s.trim().parse().ok(),
+        Value::Number(n) => n.to_string().parse().ok(),
+        _ => None,
+    }
+}
+
+fn parse_flag(value: &Value) -> Option<bool> {
+    match value {
+        Value::String(s) => match s.as_str() {
+            "Yes" | "yes" | "1" => Some(true),
+            "No" | "no" | "0" => Some(false),
+            _ => None,
+        },
+        Value::Bool(b) => Some(*b),
+        _ => None,
+    }
+}
+
+fn parse_object(value: &Value) -> Option<BTreeMap<String, Value>> {
+    let object = value.as_object()?;
+    Some(object.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+}
+
+fn number<T: Display, S: Serializer>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(v) => serializer.serialize_str(&v.to_string()),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn flag<S: Serializer>(value: &Option<bool>, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(true) => serializer.serialize_str("Yes"),
+        Some(false) => serializer.serialize_str("No"),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT: &str = r#"{
+        "creatingLibrary": {"name": "MediaInfoLib", "version": "25.07", "url": "https://mediaarea.net/MediaInfo"},
+        "media": {
+            "@ref": "samples/sample.mp4",
+            "track": [
+                {"@type": "General", "VideoCount": "1", "AudioCount": "2", "FileSize": "1055736",
+                 "Duration": "5.568", "IsStreamable": "Yes", "Format": "MPEG-4",
+                 "extra": {"com_apple_quicktime_make": "Apple"}},
+                {"@type": "Video", "StreamOrder": "0", "ID": "1", "Format": "AVC", "Width": "1920",
+                 "Height": "1080", "FrameRate": "29.970", "BitDepth": "8", "Default": "Yes",
+                 "Format_Settings_CABAC": "Yes"},
+                {"@type": "Audio", "@typeorder": "1", "Format": "AAC", "Channels": "2",
+                 "SamplingRate": "48000 / 24000", "BitRate": "bogus", "StreamSize": "1024.5",
+                 "Default": "No"},
+                {"@type": "Audio", "@typeorder": "2", "Format": "AC-3", "Channels": "6"},
+                {"@type": "Menu", "extra": {"_00_00_00_000": "Chapter 1"}}
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn parses_tracks_per_kind() {
+        let report = MediaInfoReport::from_json(REPORT).unwrap();
+
+        assert_eq!(report.creating_library.as_ref().unwrap().version, "25.07");
+        assert_eq!(report.media.reference, "samples/sample.mp4");
+        assert_eq!(report.tracks().len(), 5);
+        assert_eq!(report.tracks()[1].kind(), MediaInfoStream::Video);
+
+        let general = report.general().unwrap();
+        assert_eq!(general.audio_count, Some(2));
+        assert_eq!(general.file_size, Some(1055736));
+        assert_eq!(general.duration, Some(5.568));
+        assert_eq!(general.is_streamable, Some(true));
+        assert_eq!(general.extra["com_apple_quicktime_make"], "Apple");
+
+        let video = report.video().next().unwrap();
+        assert_eq!(video.width, Some(1920));
+        assert_eq!(video.frame_rate, Some(29.97));
+        assert_eq!(video.default, Some(true));
+        assert_eq!(video.fields["Format_Settings_CABAC"], "Yes");
+        assert!(!video.fields.contains_key("@type"));
+
+        let audio: Vec<_> = report.audio().collect();
+        assert_eq!(audio.len(), 2);
+        assert_eq!(audio[0].type_order, Some(1));
+        assert_eq!(audio[0].sampling_rate, None);
+        assert_eq!(audio[0].fields["SamplingRate"], "48000 / 24000");
+        assert_eq!(audio[0].bit_rate, None);
+        assert_eq!(audio[0].fields["BitRate"], "bogus");
+        assert_eq!(audio[0].stream_size, None);
+        assert_eq!(audio[0].fields["StreamSize"], "1024.5");
+        assert_eq!(audio[0].default, Some(false));
+        assert_eq!(audio[1].channels, Some(6));
+
+        assert_eq!(report.menus().next().unwrap().extra.len(), 1);
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let report = MediaInfoReport::from_json(REPORT).unwrap();
+        let json = report.to_json().unwrap();
+
+        assert!(json.contains(r#""@type":"Video""#));
+        assert!(json.contains(r#""Width":"1920""#));
+        assert!(json.contains(r#""IsStreamable":"Yes""#));
+        assert!(json.contains(r#""BitRate":"bogus""#));
+        assert!(json.contains(r#""SamplingRate":"48000 / 24000""#));
+        assert_eq!(report, MediaInfoReport::from_json(&json).unwrap());
+    }
+
+    #[test]
+    fn rejects_malformed_json() {
+        assert!(matches!(
+            MediaInfoReport::from_json("{\"media\": {\"track\": [{\"@type\": \"Bogus\"}]}}"),
+            Err(MediaInfoError::InvalidReport(_))
+        ));
+    }
+}
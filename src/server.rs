@@ -0,0 +1,494 @@
+// HTTP analysis service, behind the `server` feature.
+//
+// - `POST /analyze` analyzes the request body, streamed through the buffer
+//   API, or with `?path=...` a server-side file under one of the allowed
+//   directories. `?output=` selects the response: `report` (default, the
+//   typed `MediaInfoReport` as JSON), `text`, or any output format of the
+//   linked library such as `json`, `xml` or `ebucore`.
+// - `GET /health` always answers while the process runs, with the library
+//   version and the analyses in flight.
+// - `GET /ready` answers 503 while every analysis slot is taken.
+//
+// Errors are `{"error": "..."}` with 400 for bad requests, 403 for paths
+// outside the allow-list, 404 for missing files, 411/413 for bodies without
+// a length or over the limit, 422 for formats MediaInfo does not recognize
+// and 503 when busy.
+
+use crate::batch::OpenError;
+use crate::convenience_api::MediaInfoWrapper;
+
+use std::fs;
+use std::io;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServerOptions {
+    /// Uploads larger than this are refused.
+    pub max_body_size: u64,
+    /// Analyses running at once, further ones are refused until one ends.
+    pub max_concurrent: usize,
+    /// Directories whose files may be analyzed by path. Empty disables
+    /// `?path=`.
+    pub allowed_paths: Vec<PathBuf>,
+}
+
+pub struct AnalysisServer {
+    server: Arc<Server>,
+    options: Arc<ServerOptions>,
+    in_flight: Arc<AtomicUsize>,
+}
+
+// The status, content type and body of a response.
+type Reply = (u16, String, String);
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        ServerOptions {
+            max_body_size: 1 << 30,
+            max_concurrent: thread::available_parallelism().map_or(1, |x| x.get()),
+            allowed_paths: Vec::new(),
+        }
+    }
+}
+
+impl AnalysisServer {
+    /// Listens on `addr`, e.g. "127.0.0.1:8080" or port 0 for any free port.
+    pub fn bind(addr: &str, options: ServerOptions) -> io::Result<AnalysisServer> {
+        let allowed_paths = options
+            .allowed_paths
+            .iter()
+            .map(fs::canonicalize)
+            .collect::<io::Result<_>>()?;
+        let server = Server::http(addr).map_err(io::Error::other)?;
+        Ok(AnalysisServer {
+            server: Arc::new(server),
+            options: Arc::new(ServerOptions {
+                allowed_paths,
+                max_concurrent: options.max_concurrent.max(1),
+                ..options
+            }),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.server.server_addr().to_ip()
+    }
+
+    /// Serves requests until `shutdown()`. Analyses run on their own
+    /// threads, so health checks are answered while they do.
+    pub fn run(&self) {
+        for request in self.server.incoming_requests() {
+            let route = (request.method().clone(), route(request.url()).to_string());
+            match (route.0, route.1.as_str()) {
+                (Method::Get, "/health") => respond(request, self.health()),
+                (Method::Get, "/ready") => {
+                    let reply = match self.in_flight.load(Ordering::SeqCst) {
+                        n if n < self.options.max_concurrent => json_reply(200, "ready"),
+                        _ => error(503, "every analysis slot is taken"),
+                    };
+                    respond(request, reply)
+                }
+                (Method::Post, "/analyze") => self.spawn_analysis(request),
+                (_, "/health" | "/ready" | "/analyze") => {
+                    respond(request, error(405, "method not allowed"))
+                }
+                _ => respond(request, error(404, "no such endpoint")),
+            }
+        }
+    }
+
+    /// Makes `run()` return, from any thread.
+    pub fn shutdown(&self) {
+        self.server.unblock();
+    }
+
+    fn health(&self) -> Reply {
+        let version = MediaInfoWrapper::new()
+            .version()
+            .map_or("unknown".to_string(), |x| x.to_string());
+        let body = serde_json::json!({
+            "status": "ok",
+            "library_version": version,
+            "in_flight": self.in_flight.load(Ordering::SeqCst),
+            "max_concurrent": self.options.max_concurrent,
+        });
+        (200, "application/json".to_string(), body.to_string())
+    }
+
+    fn spawn_analysis(&self, request: Request) {
+        let taken = self
+            .in_flight
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < self.options.max_concurrent).then_some(n + 1)
+            });
+        if taken.is_err() {
+            respond(request, error(503, "every analysis slot is taken"));
+            return;
+        }
+
+        let options = Arc::clone(&self.options);
+        let in_flight = Arc::clone(&self.in_flight);
+        thread::spawn(move || {
+            let mut request = request;
+            let reply = analyze(&mut request, &options);
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            respond(request, reply);
+        });
+    }
+}
+
+fn route(url: &str) -> &str {
+    url.split_once('?').map_or(url, |(path, _)| path)
+}
+
+fn analyze(request: &mut Request, options: &ServerOptions) -> Reply {
+    let mut output = None;
+    let mut path = None;
+    for (name, value) in query(request.url()) {
+        match name.as_str() {
+            "output" => output = Some(value),
+            "path" => path = Some(value),
+            _ => return error(400, &format!("unknown parameter {name}")),
+        }
+    }
+
+    let mut media_info = MediaInfoWrapper::new();
+    let format = match output_format(&mut media_info, output.as_deref()) {
+        Ok(format) => format,
+        Err(reply) => return reply,
+    };
+
+    let opened = match path {
+        Some(path) => open_path(&mut media_info, Path::new(&path), options),
+        None => open_body(&mut media_info, request, options),
+    };
+    if let Err(reply) = opened {
+        return reply;
+    }
+
+    let Some((name, mime_type)) = format else {
+        return match media_info.report().and_then(|x| x.to_json()) {
+            Ok(json) => (200, "application/json".to_string(), json),
+            Err(e) => error(500, &format!("{e:?}")),
+        };
+    };
+    match media_info.inform_with_options(&[("Output", &name)]) {
+        Ok(body) => (200, mime_type, body),
+        Err(e) => error(500, &format!("{e:?}")),
+    }
+}
+
+// `None` for the typed report, otherwise the `Output` option value and the
+// content type to answer with.
+fn output_format(
+    media_info: &mut MediaInfoWrapper,
+    requested: Option<&str>,
+) -> Result<Option<(String, String)>, Reply> {
+    let requested = match requested {
+        None => return Ok(None),
+        Some(x) if x.eq_ignore_ascii_case("report") => return Ok(None),
+        Some(x) if x.eq_ignore_ascii_case("text") => {
+            return Ok(Some((String::new(), "text/plain".to_string())));
+        }
+        Some(x) => x,
+    };
+
+    match media_info.output_format(requested) {
+        Ok(Some(format)) if format.mime_type.is_empty() => {
+            Ok(Some((format.name, "application/octet-stream".to_string())))
+        }
+        Ok(Some(format)) => Ok(Some((format.name, format.mime_type))),
+        Ok(None) => Err(error(400, &format!("unknown output format {requested}"))),
+        Err(e) => Err(error(500, &format!("{e:?}"))),
+    }
+}
+
+fn open_path(
+    media_info: &mut MediaInfoWrapper,
+    path: &Path,
+    options: &ServerOptions,
+) -> Result<(), Reply> {
+    if options.allowed_paths.is_empty() {
+        return Err(error(403, "analysis by path is disabled"));
+    }
+    let path = fs::canonicalize(path).map_err(|_| error(404, "no such file"))?;
+    if !options.allowed_paths.iter().any(|x| path.starts_with(x)) {
+        return Err(error(403, "path is outside the allowed directories"));
+    }
+    if !fs::metadata(&path).map_err(io_error)?.is_file() {
+        return Err(error(400, "path is not a file"));
+    }
+    media_info.open_file(&path).map_err(|e| match e {
+        OpenError::Unreadable(e) => io_error(e),
+        OpenError::Unsupported => error(422, "unsupported format"),
+    })
+}
+
+// 403 when the server may not read the file, 500 for any other failure.
+fn io_error(e: io::Error) -> Reply {
+    match e.kind() {
+        io::ErrorKind::PermissionDenied => error(403, &e.to_string()),
+        _ => error(500, &e.to_string()),
+    }
+}
+
+fn open_body(
+    media_info: &mut MediaInfoWrapper,
+    request: &mut Request,
+    options: &ServerOptions,
+) -> Result<(), Reply> {
+    let size = match request.body_length() {
+        None => return Err(error(411, "the body needs a Content-Length")),
+        Some(0) => return Err(error(400, "empty body")),
+        Some(size) => size as u64,
+    };
+    if size > options.max_body_size {
+        return Err(error(
+            413,
+            &format!("body over the {} byte limit", options.max_body_size),
+        ));
+    }
+    media_info
+        .open_reader(request.as_reader(), size)
+        .map_err(|e| error(400, &format!("cannot read the body: {e}")))?;
+    if media_info.format().is_err() {
+        return Err(error(422, "unsupported format"));
+    }
+    Ok(())
+}
+
+// `name=value` pairs of the query string, percent-decoded.
+fn query(url: &str) -> Vec<(String, String)> {
+    let Some((_, query)) = url.split_once('?') else {
+        return Vec::new();
+    };
+    query
+        .split('&')
+        .filter(|x| !x.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(name), percent_decode(value))
+        })
+        .collect()
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|x| std::str::from_utf8(x).ok())
+            .and_then(|x| u8::from_str_radix(x, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn json_reply(status: u16, message: &str) -> Reply {
+    let body = serde_json::json!({ "status": message });
+    (status, "application/json".to_string(), body.to_string())
+}
+
+fn error(status: u16, message: &str) -> Reply {
+    let body = serde_json::json!({ "error": message });
+    (status, "application/json".to_string(), body.to_string())
+}
+
+fn respond(request: Request, (status, content_type, body): Reply) {
+    let mut response = Response::from_string(body).with_status_code(status);
+    if let Ok(header) = Header::from_bytes("Content-Type", content_type) {
+        response = response.with_header(header);
+    }
+    if status == 503
+        && let Ok(header) = Header::from_bytes("Retry-After", "1")
+    {
+        response = response.with_header(header);
+    }
+    // The client may be gone, there is no one left to tell.
+    let _ = request.respond(response);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::MediaInfoReport;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::panic::{self, AssertUnwindSafe};
+
+    fn request(addr: SocketAddr, head: &str, body: &[u8]) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream
+            .write_all(format!("{head}\r\nHost: localhost\r\nConnection: close\r\n").as_bytes())
+            .unwrap();
+        if !head.starts_with("GET") {
+            stream
+                .write_all(format!("Content-Length: {}\r\n", body.len()).as_bytes())
+                .unwrap();
+        }
+        stream.write_all(b"\r\n").unwrap();
+        let _ = stream.write_all(body);
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response
+            .split_once("\r\n\r\n")
+            .map_or(String::new(), |(_, body)| body.to_string());
+        (status, body)
+    }
+
+    fn serve(options: ServerOptions, test: impl FnOnce(SocketAddr)) {
+        let server = AnalysisServer::bind("127.0.0.1:0", options).unwrap();
+        let addr = server.local_addr().unwrap();
+        thread::scope(|scope| {
+            scope.spawn(|| server.run());
+            // A failed assertion must still stop the server, or the scope
+            // never ends.
+            let result = panic::catch_unwind(AssertUnwindSafe(|| test(addr)));
+            server.shutdown();
+            if let Err(payload) = result {
+                panic::resume_unwind(payload);
+            }
+        });
+    }
+
+    #[test]
+    fn answers_health_and_routing() {
+        serve(ServerOptions::default(), |addr| {
+            let (status, body) = request(addr, "GET /health HTTP/1.1", b"");
+            assert_eq!(status, 200);
+            let health: serde_json::Value = serde_json::from_str(&body).unwrap();
+            assert_eq!(health["status"], "ok");
+            assert_eq!(health["in_flight"], 0);
+
+            assert_eq!(request(addr, "GET /ready HTTP/1.1", b"").0, 200);
+            assert_eq!(request(addr, "GET /analyze HTTP/1.1", b"").0, 405);
+            assert_eq!(request(addr, "GET /nowhere HTTP/1.1", b"").0, 404);
+        });
+    }
+
+    #[test]
+    fn checks_requests_before_analyzing() {
+        let allowed = std::env::temp_dir().join(format!("mediainfo-server-{}", std::process::id()));
+        fs::create_dir_all(&allowed).unwrap();
+        fs::write(allowed.join("clip.bin"), b"not media").unwrap();
+        let options = ServerOptions {
+            max_body_size: 16,
+            allowed_paths: vec![allowed.clone()],
+            ..ServerOptions::default()
+        };
+
+        serve(options, |addr| {
+            let post =
+                |target: &str, body: &[u8]| request(addr, &format!("POST {target} HTTP/1.1"), body);
+            assert_eq!(post("/analyze", &[0; 17]).0, 413);
+            assert_eq!(post("/analyze", b"").0, 400);
+            assert_eq!(post("/analyze?colour=blue", b"x").0, 400);
+            assert_eq!(post("/analyze?output=report", b"not media").0, 422);
+            assert_eq!(post("/analyze?path=%2Fetc%2Fpasswd", b"").0, 403);
+
+            let missing = allowed.join("missing.bin");
+            let target = format!("/analyze?path={}", missing.display());
+            assert_eq!(post(&target, b"").0, 404);
+            let target = format!("/analyze?path={}", allowed.join("clip.bin").display());
+            let (status, body) = post(&target, b"");
+            assert_eq!(status, 422);
+            assert_eq!(body, r#"{"error":"unsupported format"}"#);
+
+            let target = format!("/analyze?path={}", allowed.display());
+            let (status, body) = post(&target, b"");
+            assert_eq!(status, 400);
+            assert_eq!(body, r#"{"error":"path is not a file"}"#);
+        });
+        fs::remove_dir_all(&allowed).unwrap();
+    }
+
+    #[test]
+    fn keeps_concurrent_output_formats_apart() {
+        let samples = fs::canonicalize("samples").unwrap();
+        let sample = samples.join("sample.mp4");
+        let options = ServerOptions {
+            max_concurrent: 8,
+            allowed_paths: vec![samples],
+            ..ServerOptions::default()
+        };
+
+        serve(options, |addr| {
+            thread::scope(|scope| {
+                for (output, prefix) in [("json", "{"), ("xml", "<"), ("text", "General")] {
+                    let head = format!(
+                        "POST /analyze?path={}&output={output} HTTP/1.1",
+                        sample.display()
+                    );
+                    scope.spawn(move || {
+                        for _ in 0..10 {
+                            let (status, body) = request(addr, &head, b"");
+                            assert_eq!(status, 200, "{output}: {body}");
+                            assert!(body.starts_with(prefix), "{output}: {body}");
+                            // The complete dump asked for by reports stays
+                            // with them.
+                            assert!(!body.contains("Kind of stream"), "{output}: {body}");
+                        }
+                    });
+                }
+                let head = format!("POST /analyze?path={} HTTP/1.1", sample.display());
+                scope.spawn(move || {
+                    for _ in 0..10 {
+                        let (status, body) = request(addr, &head, b"");
+                        assert_eq!(status, 200, "report: {body}");
+                        MediaInfoReport::from_json(&body).unwrap();
+                    }
+                });
+            });
+        });
+    }
+
+    #[test]
+    fn refuses_analyses_over_the_limit() {
+        let server = AnalysisServer::bind("127.0.0.1:0", ServerOptions::default()).unwrap();
+        let slots = server.options.max_concurrent;
+        server.in_flight.store(slots, Ordering::SeqCst);
+        let addr = server.local_addr().unwrap();
+        thread::scope(|scope| {
+            scope.spawn(|| server.run());
+            assert_eq!(request(addr, "GET /ready HTTP/1.1", b"").0, 503);
+            assert_eq!(request(addr, "POST /analyze HTTP/1.1", b"data").0, 503);
+            server.shutdown();
+        });
+    }
+
+    #[test]
+    fn decodes_query_strings() {
+        assert_eq!(
+            query("/analyze?path=%2Fmedia%2Fa+b.mkv&output=json"),
+            [
+                ("path".to_string(), "/media/a b.mkv".to_string()),
+                ("output".to_string(), "json".to_string()),
+            ]
+        );
+        assert_eq!(percent_decode("100%"), "100%");
+        assert!(query("/analyze").is_empty());
+    }
+}
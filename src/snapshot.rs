@@ -0,0 +1,376 @@
+// Owned copies of every stream, collected in one pass so they can outlive
+// the handle and be shared across threads without locking it.
+//
+// The typed accessors of `VideoStream`, `AudioStream`... are generated for
+// the snapshot types too (see `stream_accessors!` in streams.rs).
+
+use crate::chapter;
+use crate::classification::MediaClass;
+use crate::diff::{DiffOptions, MediaDiff};
+use crate::ffi::{MediaInfo, MediaInfoError, MediaInfoResult, MediaInfoStream};
+use crate::policy::{Policy, PolicyReport};
+use crate::streams::BaseStream;
+use crate::track_selection::{TrackSelection, TrackSelector};
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+macro_rules! snapshot_struct {
+    ($struct_name: ident, $stream_type: expr) => {
+        #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+        pub struct $struct_name {
+            pub stream_type: MediaInfoStream,
+            pub index: usize,
+            pub fields: BTreeMap<String, String>,
+        }
+
+        impl $struct_name {
+            pub fn get(&self, name: &str) -> Option<&str> {
+                self.fields.get(name).map(|v| v.as_str())
+            }
+
+            #[cfg(test)]
+            #[allow(dead_code)]
+            pub(crate) fn from_pairs(index: usize, pairs: &[(&str, &str)]) -> $struct_name {
+                $struct_name {
+                    stream_type: $stream_type,
+                    index,
+                    fields: pairs
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect(),
+                }
+            }
+        }
+
+        impl BaseStream for $struct_name {
+            fn stream_type(&self) -> MediaInfoStream {
+                self.stream_type
+            }
+
+            fn index(&self) -> usize {
+                self.index
+            }
+
+            fn handler(&self) -> Option<&Arc<Mutex<MediaInfo>>> {
+                None
+            }
+
+            fn field(&self, name: &str) -> MediaInfoResult<String> {
+                self.fields
+                    .get(name)
+                    .cloned()
+                    .ok_or(MediaInfoError::ZeroLengthResult)
+            }
+
+            // Positions are not kept, but only chapters are named after a
+            // start time. Chapters sharing a start time share one field
+            // name, so a snapshot keeps only the last of them.
+            fn chapter_fields(&self) -> Vec<(String, String)> {
+                self.fields
+                    .iter()
+                    .filter(|(name, _)| chapter::is_chapter_field(name))
+                    .map(|(name, value)| (name.clone(), value.clone()))
+                    .collect()
+            }
+        }
+    };
+}
+
+snapshot_struct!(GeneralSnapshot, MediaInfoStream::General);
+snapshot_struct!(VideoSnapshot, MediaInfoStream::Video);
+snapshot_struct!(AudioSnapshot, MediaInfoStream::Audio);
+snapshot_struct!(TextSnapshot, MediaInfoStream::Text);
+snapshot_struct!(OtherSnapshot, MediaInfoStream::Other);
+snapshot_struct!(ImageSnapshot, MediaInfoStream::Image);
+snapshot_struct!(MenuSnapshot, MediaInfoStream::Menu);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MediaSnapshot {
+    pub general: GeneralSnapshot,
+    pub video: Vec<VideoSnapshot>,
+    pub audio: Vec<AudioSnapshot>,
+    pub text: Vec<TextSnapshot>,
+    pub other: Vec<OtherSnapshot>,
+    pub image: Vec<ImageSnapshot>,
+    pub menu: Vec<MenuSnapshot>,
+}
+
+macro_rules! collect_streams {
+    ($handle: expr, $stream_type: expr, $struct_name: ident) => {{
+        let mut streams = Vec::new();
+        for index in 0..$handle.count_get($stream_type) {
+            streams.push($struct_name {
+                stream_type: $stream_type,
+                index,
+                fields: $handle
+                    .stream_fields($stream_type, index)?
+                    .into_iter()
+                    .collect(),
+            });
+        }
+        streams
+    }};
+}
+
+impl MediaSnapshot {
+    pub(crate) fn collect(handle: &mut MediaInfo) -> MediaInfoResult<MediaSnapshot> {
+        Ok(MediaSnapshot {
+            general: GeneralSnapshot {
+                stream_type: MediaInfoStream::General,
+                index: 0,
+                fields: handle
+                    .stream_fields(MediaInfoStream::General, 0)?
+                    .into_iter()
+                    .collect(),
+            },
+            video: collect_streams!(handle, MediaInfoStream::Video, VideoSnapshot),
+            audio: collect_streams!(handle, MediaInfoStream::Audio, AudioSnapshot),
+            text: collect_streams!(handle, MediaInfoStream::Text, TextSnapshot),
+            other: collect_streams!(handle, MediaInfoStream::Other, OtherSnapshot),
+            image: collect_streams!(handle, MediaInfoStream::Image, ImageSnapshot),
+            menu: collect_streams!(handle, MediaInfoStream::Menu, MenuSnapshot),
+        })
+    }
+
+    /// The video, audio and subtitle streams a player would pick for
+    /// `selector`.
+    pub fn select_tracks(&self, selector: &TrackSelector) -> TrackSelection {
+        selector.select(&self.video, &self.audio, &self.text)
+    }
+
+    /// Resolution class, HDR, immersive audio and the other facts derived
+    /// from the raw fields. See `MediaClass` for the rules.
+    pub fn classify(&self) -> MediaClass {
+        MediaClass::from_streams(&self.general, &self.video, &self.audio, &self.image)
+    }
+
+    /// Checks every rule of `policy` against the streams of its kind.
+    pub fn check_policy(&self, policy: &Policy) -> PolicyReport {
+        fn streams<T: BaseStream>(streams: &[T]) -> Vec<&dyn BaseStream> {
+            streams.iter().map(|x| x as &dyn BaseStream).collect()
+        }
+
+        policy.check(|kind| match kind {
+            MediaInfoStream::General => vec![&self.general as &dyn BaseStream],
+            MediaInfoStream::Video => streams(&self.video),
+            MediaInfoStream::Audio => streams(&self.audio),
+            MediaInfoStream::Text => streams(&self.text),
+            MediaInfoStream::Other => streams(&self.other),
+            MediaInfoStream::Image => streams(&self.image),
+            MediaInfoStream::Menu => streams(&self.menu),
+            MediaInfoStream::Max => Vec::new(),
+        })
+    }
+
+    /// The streams and fields that differ in `other`, this snapshot being
+    /// the old side.
+    pub fn diff(&self, other: &MediaSnapshot, options: &DiffOptions) -> MediaDiff {
+        MediaDiff::between(self, other, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::FormatName;
+    use crate::frame_rate::FrameRate;
+    use std::time::Duration;
+
+    #[test]
+    fn typed_accessors_read_from_fields() {
+        let snapshot = VideoSnapshot::from_pairs(
+            0,
+            &[
+                ("Width", "1920"),
+                ("Height", "1080"),
+                ("ScanType", "Interlaced"),
+                ("Duration", "5568"),
+            ],
+        );
+
+        assert_eq!(snapshot.width().unwrap(), 1920);
+        assert_eq!(snapshot.frame_size().unwrap(), "1920x1080");
+        assert!(snapshot.interlaced());
+        assert_eq!(snapshot.duration().unwrap().as_millis(), 5568);
+        assert!(matches!(
+            snapshot.format(),
+            Err(MediaInfoError::ZeroLengthResult)
+        ));
+        assert_eq!(snapshot.get("Width"), Some("1920"));
+    }
+
+    #[test]
+    fn generated_accessors_use_consistent_types() {
+        let snapshot = VideoSnapshot::from_pairs(
+            0,
+            &[
+                ("BitRate", "551194"),
+                ("FrameRate", "23.976"),
+                ("colour_primaries", "BT.709"),
+                ("FrameRate_Original_Num", "30000"),
+            ],
+        );
+
+        assert_eq!(snapshot.bit_rate().unwrap(), 551194);
+        assert_eq!(
+            snapshot.frame_rate().unwrap(),
+            FrameRate::new(24000, 1001).unwrap()
+        );
+        assert_eq!(snapshot.colour_primaries().unwrap(), "BT.709");
+        assert!(!snapshot.hdr_info().is_hdr());
+        assert_eq!(snapshot.frame_rate_original_num().unwrap(), 30000);
+
+        let text = TextSnapshot::from_pairs(0, &[("Lines_MaxCharacterCount", "42")]);
+        assert_eq!(text.lines_max_character_count().unwrap(), 42);
+    }
+
+    #[test]
+    fn numeric_accessors_accept_decimal_values() {
+        let snapshot = VideoSnapshot::from_pairs(
+            0,
+            &[
+                ("Duration", "5568.000"),
+                ("BitRate", "551194.5"),
+                ("DisplayAspectRatio", "1.778"),
+                ("StreamSize_Proportion", "0.91234"),
+            ],
+        );
+
+        assert_eq!(snapshot.duration().unwrap(), Duration::from_millis(5568));
+        assert_eq!(snapshot.bit_rate().unwrap(), 551195);
+        assert_eq!(snapshot.display_aspect_ratio().unwrap(), 1.778);
+        assert_eq!(snapshot.stream_size_proportion().unwrap(), 0.91234);
+    }
+
+    #[test]
+    fn list_fields_split_into_entries() {
+        let general = GeneralSnapshot::from_pairs(
+            0,
+            &[
+                ("CodecID_Compatible", "isom/iso2/avc1/mp41"),
+                ("Audio_Language_List", "English /  / French"),
+                ("Format_Profile", "Main / High"),
+                ("Audio_Format_WithHint_List", "MPEG Audio (MP3) / AAC"),
+            ],
+        );
+
+        assert_eq!(
+            general.codec_id_compatible().unwrap(),
+            ["isom", "iso2", "avc1", "mp41"]
+        );
+        let languages = general.audio_language_list().unwrap();
+        let tags: Vec<&str> = languages.iter().map(|x| x.as_str()).collect();
+        assert_eq!(tags, ["en", "und", "fr"]);
+        assert_eq!(languages[0].original(), "English");
+        assert_eq!(
+            general.field_values("Format_Profile").unwrap(),
+            ["Main", "High"]
+        );
+        let profiles = general.format_profile_list().unwrap();
+        assert_eq!(profiles[1].profile, "High");
+        assert_eq!(
+            general.audio_format_with_hint_list().unwrap(),
+            [
+                FormatName::parse("MPEG Audio (MP3)"),
+                FormatName::parse("AAC")
+            ]
+        );
+        assert!(general.video_format_list().is_err());
+
+        let audio = AudioSnapshot::from_pairs(
+            0,
+            &[(
+                "ChannelPositions",
+                "Object Based / Front: L C R, Side: L R, LFE",
+            )],
+        );
+        let layers = audio.channel_positions_list().unwrap();
+        assert!(layers[0].object_based);
+        assert_eq!(layers[1].notation(), "5.1");
+    }
+
+    #[test]
+    fn frame_rates_prefer_exact_rationals() {
+        let snapshot = VideoSnapshot::from_pairs(
+            0,
+            &[
+                ("FrameRate", "29.970"),
+                ("FrameRate_Num", "30000"),
+                ("FrameRate_Den", "1001"),
+                ("FrameRate_Nominal", "25.000"),
+            ],
+        );
+
+        assert!(snapshot.frame_rate().unwrap().is_ntsc());
+        assert_eq!(snapshot.frame_rate_nominal().unwrap().to_string(), "25");
+        assert!(snapshot.frame_rate_maximum().is_err());
+    }
+
+    #[test]
+    fn menu_snapshots_list_chapters() {
+        let menu = MenuSnapshot::from_pairs(
+            0,
+            &[
+                ("Chapters_Pos_Begin", "40"),
+                ("Chapters_Pos_End", "42"),
+                ("00:00:00.000", "en:Intro"),
+                ("00:01:30.000", "en:Credits"),
+                ("Duration_End", "00:01:30.000"),
+            ],
+        );
+
+        let chapters = menu.chapters();
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[1].start, Duration::from_secs(90));
+        assert_eq!(chapters[1].title, "Credits");
+        assert_eq!(menu.duration_end().unwrap(), Duration::from_secs(90));
+    }
+
+    #[test]
+    fn timecodes_use_the_stream_frame_rate() {
+        let snapshot = VideoSnapshot::from_pairs(
+            0,
+            &[
+                ("FrameRate", "29.970"),
+                ("TimeCode_FirstFrame", "01:00:00:00"),
+                ("TimeCode_DropFrame", "Yes"),
+            ],
+        );
+
+        let start = snapshot.first_timecode().unwrap();
+        assert!(start.is_drop_frame());
+        assert_eq!(start.to_string(), "01:00:00;00");
+        assert!(snapshot.last_timecode().is_err());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn codec_info_reads_the_codec_id_info() {
+        let fields = [
+            ("CodecID/Info", "Advanced Video Coding"),
+            ("Codec/Info", "Old"),
+        ];
+        let video = VideoSnapshot::from_pairs(0, &fields);
+        let audio = AudioSnapshot::from_pairs(0, &fields);
+        let text = TextSnapshot::from_pairs(0, &fields);
+
+        assert_eq!(video.codec_info().unwrap(), video.codec_id_info().unwrap());
+        assert_eq!(audio.codec_info().unwrap(), audio.codec_id_info().unwrap());
+        assert_eq!(text.codec_info().unwrap(), text.codec_id_info().unwrap());
+        assert_eq!(video.codec_info().unwrap(), "Advanced Video Coding");
+        assert_eq!(video.codec_info_legacy().unwrap(), "Old");
+    }
+
+    #[test]
+    fn snapshot_is_shareable() {
+        fn assert_send_sync<T: Send + Sync + Clone + Serialize>() {}
+        assert_send_sync::<MediaSnapshot>();
+
+        let snapshot = VideoSnapshot::from_pairs(0, &[("Format", "AVC")]);
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let back: VideoSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.format().unwrap(), "AVC");
+    }
+}
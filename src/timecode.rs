@@ -0,0 +1,336 @@
+// SMPTE ST 12 timecodes.
+//
+// MediaInfo prints timecodes as "HH:MM:SS:FF", with the last colon replaced
+// by a semicolon for drop-frame timecodes ("01:00:00;00"). A `Timecode` is
+// kept as a frame count since 00:00:00:00 together with the frame rate, so
+// that drop-frame labels, frame counts and durations convert exactly.
+
+use crate::frame_rate::FrameRate;
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Sub};
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Timecode {
+    frames: u64,
+    rate: FrameRate,
+    drop_frame: bool,
+}
+
+impl Timecode {
+    /// Returns `None` for out of range components, for labels skipped by
+    /// drop-frame counting (e.g. 00:01:00;00) and for drop-frame at rates
+    /// that are not a multiple of 30000/1001.
+    pub fn new(
+        hours: u32,
+        minutes: u32,
+        seconds: u32,
+        frames: u32,
+        rate: FrameRate,
+        drop_frame: bool,
+    ) -> Option<Timecode> {
+        let base = timecode_base(rate)?;
+        if hours >= 24 || minutes >= 60 || seconds >= 60 || frames as u64 >= base {
+            return None;
+        }
+        if drop_frame && !supports_drop_frame(rate) {
+            return None;
+        }
+
+        let total_minutes = 60 * hours as u64 + minutes as u64;
+        let mut count = ((total_minutes * 60) + seconds as u64) * base + frames as u64;
+        if drop_frame {
+            let dropped = dropped_per_minute(base);
+            if seconds == 0 && !minutes.is_multiple_of(10) && (frames as u64) < dropped {
+                return None;
+            }
+            count -= dropped * (total_minutes - total_minutes / 10);
+        }
+
+        Some(Timecode {
+            frames: count,
+            rate,
+            drop_frame,
+        })
+    }
+
+    /// Parses "HH:MM:SS:FF", or "HH:MM:SS;FF" for drop-frame. A value
+    /// MediaInfo joined with others (" / ") is read from its first entry.
+    pub fn parse(value: &str, rate: FrameRate) -> Option<Timecode> {
+        let value = value.split(" / ").next()?.trim();
+        let drop_frame = value.contains(';');
+        let parts: Vec<u32> = value
+            .split([':', ';', '.'])
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?;
+
+        match parts.as_slice() {
+            [hours, minutes, seconds, frames] => {
+                Timecode::new(*hours, *minutes, *seconds, *frames, rate, drop_frame)
+            }
+            _ => None,
+        }
+    }
+
+    /// The timecode `frames` frames after 00:00:00:00, wrapping at 24 hours.
+    pub fn from_frames(frames: u64, rate: FrameRate, drop_frame: bool) -> Option<Timecode> {
+        let base = timecode_base(rate)?;
+        if drop_frame && !supports_drop_frame(rate) {
+            return None;
+        }
+
+        let timecode = Timecode {
+            frames: 0,
+            rate,
+            drop_frame,
+        };
+        Some(Timecode {
+            frames: frames % timecode.frames_per_day(base),
+            ..timecode
+        })
+    }
+
+    /// The timecode of the frame shown at `duration` after 00:00:00:00.
+    pub fn from_duration(
+        duration: Duration,
+        rate: FrameRate,
+        drop_frame: bool,
+    ) -> Option<Timecode> {
+        let frames = frames_in(duration, rate);
+        Timecode::from_frames(u64::try_from(frames).ok()?, rate, drop_frame)
+    }
+
+    /// Frames since 00:00:00:00.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn rate(&self) -> FrameRate {
+        self.rate
+    }
+
+    pub fn is_drop_frame(&self) -> bool {
+        self.drop_frame
+    }
+
+    /// Real time elapsed since 00:00:00:00 at the actual frame rate. For
+    /// drop-frame timecodes this stays close to the wall clock label.
+    pub fn to_duration(self) -> Duration {
+        let nanos = self.frames as u128 * self.rate.denominator() as u128 * 1_000_000_000
+            / self.rate.numerator() as u128;
+        Duration::from_nanos(nanos as u64)
+    }
+
+    /// (hours, minutes, seconds, frames) as displayed.
+    pub fn components(&self) -> (u32, u32, u32, u32) {
+        let base = timecode_base(self.rate).unwrap_or(1);
+        let mut frames = self.frames;
+
+        if self.drop_frame {
+            let dropped = dropped_per_minute(base);
+            let per_ten_minutes = base * 600 - dropped * 9;
+            let per_minute = base * 60 - dropped;
+            let tens = frames / per_ten_minutes;
+            let rest = frames % per_ten_minutes;
+            frames += dropped * 9 * tens;
+            if rest > dropped {
+                frames += dropped * ((rest - dropped) / per_minute);
+            }
+        }
+
+        (
+            (frames / (base * 3600)) as u32,
+            (frames / (base * 60) % 60) as u32,
+            (frames / base % 60) as u32,
+            (frames % base) as u32,
+        )
+    }
+
+    /// `frames` later, or earlier when negative, wrapping at 24 hours.
+    pub fn offset(&self, frames: i64) -> Timecode {
+        let base = timecode_base(self.rate).unwrap_or(1);
+        let day = self.frames_per_day(base) as i128;
+        let frames = (self.frames as i128 + frames as i128).rem_euclid(day);
+        Timecode {
+            frames: frames as u64,
+            ..*self
+        }
+    }
+
+    fn frames_per_day(&self, base: u64) -> u64 {
+        let day = base * 86_400;
+        if self.drop_frame {
+            day - dropped_per_minute(base) * (1440 - 144)
+        } else {
+            day
+        }
+    }
+}
+
+// Whole frames shown at `rate` within `duration`, without wrapping at 24
+// hours.
+fn frames_in(duration: Duration, rate: FrameRate) -> u128 {
+    duration.as_nanos() * rate.numerator() as u128 / (rate.denominator() as u128 * 1_000_000_000)
+}
+
+// The integer frame count per timecode second, e.g. 30 for 29.97.
+fn timecode_base(rate: FrameRate) -> Option<u64> {
+    match rate.nominal() {
+        0 => None,
+        base => Some(base),
+    }
+}
+
+fn supports_drop_frame(rate: FrameRate) -> bool {
+    rate.is_ntsc() && rate.nominal().is_multiple_of(30)
+}
+
+// 2 frames at 29.97, 4 at 59.94.
+fn dropped_per_minute(base: u64) -> u64 {
+    base / 15
+}
+
+impl Add<i64> for Timecode {
+    type Output = Timecode;
+
+    fn add(self, frames: i64) -> Timecode {
+        self.offset(frames)
+    }
+}
+
+impl Sub<i64> for Timecode {
+    type Output = Timecode;
+
+    fn sub(self, frames: i64) -> Timecode {
+        self.offset(-frames)
+    }
+}
+
+/// The number of frames between two timecodes, counted at the rate of the
+/// left-hand side.
+impl Sub for Timecode {
+    type Output = i64;
+
+    fn sub(self, other: Timecode) -> i64 {
+        let other = if self.rate == other.rate {
+            other.frames
+        } else {
+            u64::try_from(frames_in(other.to_duration(), self.rate))
+                .expect("a timecode spans less than 24 hours at any frame rate")
+        };
+        self.frames as i64 - other as i64
+    }
+}
+
+impl PartialOrd for Timecode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Ordered by time, with the frame count, rate and drop-frame flag as tie
+// breaks so that only equal timecodes compare `Equal`.
+impl Ord for Timecode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let rate = |x: &Timecode| (x.rate.numerator(), x.rate.denominator());
+        self.to_duration()
+            .cmp(&other.to_duration())
+            .then(self.frames.cmp(&other.frames))
+            .then(rate(self).cmp(&rate(other)))
+            .then(self.drop_frame.cmp(&other.drop_frame))
+    }
+}
+
+impl fmt::Display for Timecode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (hours, minutes, seconds, frames) = self.components();
+        let separator = if self.drop_frame { ';' } else { ':' };
+        write!(
+            f,
+            "{hours:02}:{minutes:02}:{seconds:02}{separator}{frames:02}"
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ntsc() -> FrameRate {
+        FrameRate::new(30000, 1001).unwrap()
+    }
+
+    fn pal() -> FrameRate {
+        FrameRate::new(25, 1).unwrap()
+    }
+
+    #[test]
+    fn parses_and_prints_timecodes() {
+        let tc = Timecode::parse("01:00:00:00", pal()).unwrap();
+        assert_eq!(tc.frames(), 90_000);
+        assert_eq!(tc.to_duration(), Duration::from_secs(3600));
+        assert_eq!(tc.to_string(), "01:00:00:00");
+
+        let df = Timecode::parse("01:00:00;00", ntsc()).unwrap();
+        assert!(df.is_drop_frame());
+        assert_eq!(df.frames(), 107_892);
+        assert_eq!(df.to_string(), "01:00:00;00");
+
+        assert_eq!(Timecode::parse("00:00:00:25", pal()), None);
+        assert_eq!(Timecode::parse("00:01:00;00", ntsc()), None);
+        assert_eq!(Timecode::parse("00:00:00;00", pal()), None);
+    }
+
+    #[test]
+    fn counts_drop_frames() {
+        let before = Timecode::parse("00:00:59;29", ntsc()).unwrap();
+        assert_eq!((before + 1).to_string(), "00:01:00;02");
+
+        let tenth = Timecode::parse("00:09:59;29", ntsc()).unwrap();
+        assert_eq!((tenth + 1).to_string(), "00:10:00;00");
+
+        for frames in [0, 1799, 1800, 17_982, 107_891, 2_589_407] {
+            let tc = Timecode::from_frames(frames, ntsc(), true).unwrap();
+            assert_eq!(Timecode::parse(&tc.to_string(), ntsc()), Some(tc));
+        }
+    }
+
+    #[test]
+    fn supports_arithmetic() {
+        let start = Timecode::parse("10:00:00:00", pal()).unwrap();
+        let end = start + 250;
+        assert_eq!(end.to_string(), "10:00:10:00");
+        assert_eq!(end - start, 250);
+        assert_eq!((end - 250), start);
+        assert!(start < end);
+
+        let midnight = Timecode::parse("23:59:59:24", pal()).unwrap() + 1;
+        assert_eq!(midnight.to_string(), "00:00:00:00");
+
+        let from_duration = Timecode::from_duration(Duration::from_secs(60), ntsc(), true).unwrap();
+        assert_eq!(from_duration.to_string(), "00:00:59;28");
+    }
+
+    #[test]
+    fn compares_across_rates() {
+        let pal_hour = Timecode::parse("01:00:00:00", pal()).unwrap();
+        let ntsc_zero = Timecode::parse("00:00:00;00", ntsc()).unwrap();
+        assert_eq!(pal_hour - ntsc_zero, 90_000);
+
+        // Just before midnight at 25 fps is past the last drop-frame label,
+        // which must not wrap around to the first frame.
+        let late = Timecode::parse("23:59:59:24", pal()).unwrap();
+        assert_eq!(ntsc_zero - late, -2_589_409);
+
+        let pal_zero = Timecode::parse("00:00:00:00", pal()).unwrap();
+        let ntsc_ndf = Timecode::parse("00:00:00:00", ntsc()).unwrap();
+        for (a, b) in [(pal_zero, ntsc_zero), (ntsc_ndf, ntsc_zero)] {
+            assert_ne!(a, b);
+            assert_ne!(a.cmp(&b), Ordering::Equal);
+            assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+        }
+    }
+}
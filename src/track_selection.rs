@@ -0,0 +1,431 @@
+// Default track selection for players and transcoders.
+//
+// The flags MediaInfo reads from the container (`Default`, `Forced`,
+// `AlternateGroup`), the language, `ServiceKind` (AC-3 style codes: "HI" for
+// hearing impaired, "C" for commentary) and the title decide which video,
+// audio and subtitle streams a player would pick for a set of preferences.
+
+use crate::language::LanguageTag;
+use crate::parsing;
+use crate::streams::BaseStream;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChannelPreference {
+    /// The most channels, e.g. 5.1 over stereo.
+    #[default]
+    Most,
+    Fewest,
+    Any,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SubtitlePreference {
+    Off,
+    /// Forced subtitles only, preferably in the language of the selected
+    /// audio.
+    #[default]
+    Forced,
+    /// Full subtitles in one of the preferred languages.
+    Full,
+    /// Subtitles for the deaf and hard of hearing, or full subtitles when
+    /// there are none.
+    Sdh,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackSelector {
+    /// Language ranges in order of preference, e.g. `["fr", "en"]`.
+    pub languages: Vec<String>,
+    pub channels: ChannelPreference,
+    pub allow_commentary: bool,
+    pub subtitles: SubtitlePreference,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SelectionReason {
+    Default,
+    Forced,
+    HearingImpaired,
+    /// Matched the preferred language range.
+    Language(String),
+    /// Forced subtitles in the language of the selected audio.
+    AudioLanguage(String),
+    /// In the same alternate group as a default track.
+    AlternateGroup(u64),
+    Channels(u64),
+    BitRate(u64),
+    /// Nothing else told the candidates apart.
+    FirstStream,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SelectedTrack {
+    /// The index of the stream among the streams of its kind.
+    pub index: usize,
+    pub reasons: Vec<SelectionReason>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrackSelection {
+    pub video: Option<SelectedTrack>,
+    pub audio: Option<SelectedTrack>,
+    pub text: Option<SelectedTrack>,
+}
+
+impl fmt::Display for SelectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectionReason::Default => f.write_str("default track"),
+            SelectionReason::Forced => f.write_str("forced"),
+            SelectionReason::HearingImpaired => f.write_str("for the hearing impaired"),
+            SelectionReason::Language(x) => write!(f, "language {x}"),
+            SelectionReason::AudioLanguage(x) => write!(f, "matches the audio language {x}"),
+            SelectionReason::AlternateGroup(x) => write!(f, "alternate group {x}"),
+            SelectionReason::Channels(x) => write!(f, "{x} channels"),
+            SelectionReason::BitRate(x) => write!(f, "{x} b/s"),
+            SelectionReason::FirstStream => f.write_str("first stream"),
+        }
+    }
+}
+
+impl TrackSelector {
+    pub(crate) fn select<V, A, T>(&self, video: &[V], audio: &[A], text: &[T]) -> TrackSelection
+    where
+        V: BaseStream,
+        A: BaseStream,
+        T: BaseStream,
+    {
+        let video = self.select_video(video);
+        let audio = self.select_audio(audio);
+        let audio_language = audio.as_ref().and_then(|(_, language)| language.clone());
+        let text = self.select_text(text, audio_language.as_ref());
+
+        TrackSelection {
+            video,
+            audio: audio.map(|(track, _)| track),
+            text,
+        }
+    }
+
+    fn select_video<V: BaseStream>(&self, streams: &[V]) -> Option<SelectedTrack> {
+        let candidates: Vec<Candidate> = streams.iter().map(|x| self.candidate(x)).collect();
+        let best = candidates.iter().min_by_key(|c| (!c.default, c.index))?;
+        Some(best.selected(Vec::new()))
+    }
+
+    // The selected track and its language, which picks forced subtitles.
+    fn select_audio<A: BaseStream>(
+        &self,
+        streams: &[A],
+    ) -> Option<(SelectedTrack, Option<LanguageTag>)> {
+        let all: Vec<Candidate> = streams.iter().map(|x| self.candidate(x)).collect();
+        let mut candidates: Vec<&Candidate> = all
+            .iter()
+            .filter(|c| self.allow_commentary || !c.commentary)
+            .collect();
+        if candidates.is_empty() {
+            candidates = all.iter().collect();
+        }
+        let default_groups: Vec<u64> = all
+            .iter()
+            .filter(|c| c.default)
+            .filter_map(|c| c.group)
+            .collect();
+        let in_default_group = |c: &Candidate| c.group.is_some_and(|g| default_groups.contains(&g));
+
+        let channels = |c: &Candidate| match self.channels {
+            ChannelPreference::Most => u64::MAX - c.channels.unwrap_or(0),
+            ChannelPreference::Fewest => c.channels.unwrap_or(u64::MAX),
+            ChannelPreference::Any => 0,
+        };
+        let best = *candidates.iter().min_by_key(|c| {
+            (
+                c.rank,
+                !c.default,
+                !in_default_group(c),
+                channels(c),
+                u64::MAX - c.bit_rate.unwrap_or(0),
+                c.index,
+            )
+        })?;
+
+        let mut extra = Vec::new();
+        if let Some(group) = best
+            .group
+            .filter(|_| !best.default && in_default_group(best))
+        {
+            extra.push(SelectionReason::AlternateGroup(group));
+        }
+        if candidates.len() > 1 {
+            if let Some(x) = best
+                .channels
+                .filter(|_| self.channels != ChannelPreference::Any)
+            {
+                extra.push(SelectionReason::Channels(x));
+            }
+            if let Some(x) = best.bit_rate {
+                extra.push(SelectionReason::BitRate(x));
+            }
+        }
+
+        Some((best.selected(extra), best.language.clone()))
+    }
+
+    fn select_text<T: BaseStream>(
+        &self,
+        streams: &[T],
+        audio_language: Option<&LanguageTag>,
+    ) -> Option<SelectedTrack> {
+        let all: Vec<Candidate> = streams.iter().map(|x| self.candidate(x)).collect();
+        let usable = |c: &&Candidate| {
+            (self.allow_commentary || !c.commentary)
+                && (self.languages.is_empty() || c.rank < self.languages.len())
+        };
+        let full = || {
+            all.iter()
+                .filter(|c| !c.forced && !c.sdh)
+                .filter(usable)
+                .min_by_key(|c| (c.rank, !c.default, c.index))
+        };
+
+        match self.subtitles {
+            SubtitlePreference::Off => None,
+            SubtitlePreference::Forced => {
+                let audio_match = |c: &Candidate| {
+                    c.language
+                        .as_ref()
+                        .zip(audio_language)
+                        .is_some_and(|(x, audio)| x.matches(audio.as_str()))
+                };
+                let best = all
+                    .iter()
+                    .filter(|c| c.forced)
+                    .filter(|c| audio_match(c) || usable(c))
+                    .min_by_key(|c| (!audio_match(c), c.rank, !c.default, c.index))?;
+
+                let mut extra = Vec::new();
+                if let Some(language) = best.language.as_ref().filter(|_| audio_match(best)) {
+                    extra.push(SelectionReason::AudioLanguage(language.to_string()));
+                }
+                Some(best.selected(extra))
+            }
+            SubtitlePreference::Full => full().map(|c| c.selected(Vec::new())),
+            SubtitlePreference::Sdh => all
+                .iter()
+                .filter(|c| c.sdh && !c.forced)
+                .filter(usable)
+                .min_by_key(|c| (c.rank, !c.default, c.index))
+                .or_else(full)
+                .map(|c| c.selected(Vec::new())),
+        }
+    }
+
+    fn candidate<S: BaseStream>(&self, stream: &S) -> Candidate {
+        let text = |name: &str| stream.field(name).unwrap_or_default();
+        let number = |name: &str| parsing::parse_u64(&text(name));
+        let flag = |name: &str| text(name) == "Yes";
+
+        let language = LanguageTag::parse(&text("Language"));
+        let (rank, range) = language
+            .as_ref()
+            .and_then(|tag| {
+                self.languages
+                    .iter()
+                    .enumerate()
+                    .find(|(_, range)| tag.matches(range))
+            })
+            .map_or((self.languages.len(), None), |(i, range)| {
+                (i, Some(range.clone()))
+            });
+
+        let kinds: Vec<String> = text("ServiceKind")
+            .split([' ', '/', '-'])
+            .filter(|x| !x.is_empty())
+            .map(str::to_string)
+            .collect();
+        let title = text("Title").to_lowercase();
+
+        Candidate {
+            index: stream.index(),
+            language,
+            range,
+            rank,
+            default: flag("Default"),
+            forced: flag("Forced"),
+            commentary: kinds.iter().any(|x| x == "C") || title.contains("commentary"),
+            sdh: kinds.iter().any(|x| x == "HI")
+                || title.contains("sdh")
+                || title.contains("hearing impaired"),
+            group: number("AlternateGroup"),
+            channels: number("Channel(s)"),
+            bit_rate: number("BitRate"),
+        }
+    }
+}
+
+struct Candidate {
+    index: usize,
+    language: Option<LanguageTag>,
+    // The preference range the language matched, and its position.
+    range: Option<String>,
+    rank: usize,
+    default: bool,
+    forced: bool,
+    commentary: bool,
+    sdh: bool,
+    group: Option<u64>,
+    channels: Option<u64>,
+    bit_rate: Option<u64>,
+}
+
+impl Candidate {
+    fn selected(&self, extra: Vec<SelectionReason>) -> SelectedTrack {
+        let mut reasons = Vec::new();
+        if let Some(language) = self.language.as_ref().filter(|_| self.range.is_some()) {
+            reasons.push(SelectionReason::Language(language.to_string()));
+        }
+        if self.default {
+            reasons.push(SelectionReason::Default);
+        }
+        if self.forced {
+            reasons.push(SelectionReason::Forced);
+        }
+        if self.sdh {
+            reasons.push(SelectionReason::HearingImpaired);
+        }
+        reasons.extend(extra);
+        if reasons.is_empty() {
+            reasons.push(SelectionReason::FirstStream);
+        }
+
+        SelectedTrack {
+            index: self.index,
+            reasons,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::{AudioSnapshot, TextSnapshot, VideoSnapshot};
+
+    fn selector(languages: &[&str]) -> TrackSelector {
+        TrackSelector {
+            languages: languages.iter().map(|x| x.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn picks_audio_by_language_then_flags_and_channels() {
+        let streams = [
+            AudioSnapshot::from_pairs(
+                0,
+                &[("Language", "en"), ("Default", "Yes"), ("Channel(s)", "2")],
+            ),
+            AudioSnapshot::from_pairs(1, &[("Language", "fr"), ("Channel(s)", "2")]),
+            AudioSnapshot::from_pairs(
+                2,
+                &[
+                    ("Language", "fr"),
+                    ("Channel(s)", "6"),
+                    ("BitRate", "448000"),
+                ],
+            ),
+            AudioSnapshot::from_pairs(
+                3,
+                &[
+                    ("Language", "fr"),
+                    ("Channel(s)", "2"),
+                    ("Title", "Director's commentary"),
+                ],
+            ),
+        ];
+        let no_video: [VideoSnapshot; 0] = [];
+        let no_text: [TextSnapshot; 0] = [];
+
+        let french = selector(&["fr", "en"]).select(&no_video, &streams, &no_text);
+        let audio = french.audio.unwrap();
+        assert_eq!(audio.index, 2);
+        assert_eq!(
+            audio.reasons,
+            [
+                SelectionReason::Language("fr".to_string()),
+                SelectionReason::Channels(6),
+                SelectionReason::BitRate(448000),
+            ]
+        );
+
+        let fewest = TrackSelector {
+            channels: ChannelPreference::Fewest,
+            ..selector(&["fr"])
+        };
+        let selection = fewest.select(&no_video, &streams, &no_text);
+        assert_eq!(selection.audio.unwrap().index, 1);
+
+        let german = selector(&["de"]).select(&no_video, &streams, &no_text);
+        let audio = german.audio.unwrap();
+        assert_eq!(audio.index, 0);
+        assert_eq!(audio.reasons[0], SelectionReason::Default);
+        assert!(german.video.is_none());
+    }
+
+    #[test]
+    fn picks_forced_subtitles_for_the_audio_language() {
+        let audio_streams = [AudioSnapshot::from_pairs(0, &[("Language", "ja")])];
+        let text_streams = [
+            TextSnapshot::from_pairs(0, &[("Language", "en")]),
+            TextSnapshot::from_pairs(1, &[("Language", "en"), ("Forced", "Yes")]),
+            TextSnapshot::from_pairs(2, &[("Language", "ja"), ("Forced", "Yes")]),
+            TextSnapshot::from_pairs(3, &[("Language", "en"), ("ServiceKind", "HI")]),
+        ];
+        let no_video: [VideoSnapshot; 0] = [];
+
+        let forced = selector(&["en"]).select(&no_video, &audio_streams, &text_streams);
+        let text = forced.text.unwrap();
+        assert_eq!(text.index, 2);
+        assert!(
+            text.reasons
+                .contains(&SelectionReason::AudioLanguage("ja".to_string()))
+        );
+
+        let full = TrackSelector {
+            subtitles: SubtitlePreference::Full,
+            ..selector(&["en"])
+        };
+        assert_eq!(
+            full.select(&no_video, &audio_streams, &text_streams)
+                .text
+                .unwrap()
+                .index,
+            0
+        );
+
+        let sdh = TrackSelector {
+            subtitles: SubtitlePreference::Sdh,
+            ..selector(&["en"])
+        };
+        let text = sdh
+            .select(&no_video, &audio_streams, &text_streams)
+            .text
+            .unwrap();
+        assert_eq!(text.index, 3);
+        assert!(text.reasons.contains(&SelectionReason::HearingImpaired));
+
+        let french = TrackSelector {
+            subtitles: SubtitlePreference::Full,
+            ..selector(&["fr"])
+        };
+        assert!(
+            french
+                .select(&no_video, &audio_streams, &text_streams)
+                .text
+                .is_none()
+        );
+    }
+}
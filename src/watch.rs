@@ -0,0 +1,516 @@
+// Watch folders: analyze files once they are complete.
+//
+// Events come from `notify` (inotify on Linux, FSEvents, kqueue or
+// ReadDirectoryChangesW elsewhere) and only mark a file as pending; whether
+// it is complete is decided by polling its size and modification time, see
+// `Completion`. Files already in the folder when watching starts are picked
+// up too. Names starting with a dot, the usual convention for partial
+// uploads, are ignored.
+//
+// A processed file is identified by its path, size and modification time.
+// The ledger keeps those across restarts, by default in a hidden file of the
+// folder itself, and with sidecar output a sidecar newer than its file also
+// counts as processed. A file rewritten later is analyzed again.
+
+use crate::batch::{self, BatchStatus};
+use crate::ffi::MediaInfo;
+use crate::snapshot::MediaSnapshot;
+
+use notify::event::{AccessKind, AccessMode};
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Completion {
+    /// Complete once its size and modification time have not changed for
+    /// this long.
+    StableSize(Duration),
+    /// For files written with long pauses, such as live recordings: complete
+    /// when the writer closes them, which only inotify reports, or after this
+    /// long without change.
+    Growing(Duration),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WatchFolder {
+    pub path: PathBuf,
+    pub recursive: bool,
+    pub completion: Completion,
+    /// Where processed files are recorded, so a restart does not analyze
+    /// them again. `None` analyzes every file on each start.
+    pub ledger: Option<PathBuf>,
+    /// How often pending files are checked.
+    pub poll_interval: Duration,
+}
+
+pub enum WatchOutput {
+    Callback(Box<dyn FnMut(&WatchRecord) + Send>),
+    /// `<dir>/<path relative to the folder>.json` for every file.
+    Sidecars(PathBuf),
+    /// One JSON object per line.
+    Stdout,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WatchRecord {
+    pub path: PathBuf,
+    pub size: u64,
+    /// Milliseconds since the Unix epoch.
+    pub modified_ms: u64,
+    pub status: BatchStatus,
+    pub error: Option<String>,
+    pub media: Option<MediaSnapshot>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+struct Processed {
+    path: PathBuf,
+    size: u64,
+    modified_ms: u64,
+}
+
+struct Pending {
+    size: u64,
+    modified_ms: u64,
+    changed: Instant,
+    closed: bool,
+}
+
+impl Default for Completion {
+    fn default() -> Self {
+        Completion::StableSize(Duration::from_secs(5))
+    }
+}
+
+impl WatchFolder {
+    /// Keeps its ledger in `<path>/.mediainfo-watch.ndjson`.
+    pub fn new(path: &Path) -> WatchFolder {
+        WatchFolder {
+            path: path.to_path_buf(),
+            recursive: true,
+            completion: Completion::default(),
+            ledger: Some(path.join(".mediainfo-watch.ndjson")),
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+
+    /// Watches until the watcher fails.
+    pub fn run(&self, output: WatchOutput) -> io::Result<()> {
+        self.run_until(output, &AtomicBool::new(false))
+    }
+
+    /// Watches until `stop` is set, checked every `poll_interval`.
+    pub fn run_until(&self, mut output: WatchOutput, stop: &AtomicBool) -> io::Result<()> {
+        // Events carry absolute paths, which the scan, the ledger and the
+        // sidecar paths have to match.
+        let folder = WatchFolder {
+            path: fs::canonicalize(&self.path)?,
+            ledger: self
+                .ledger
+                .as_deref()
+                .map(std::path::absolute)
+                .transpose()?,
+            ..self.clone()
+        };
+        if let WatchOutput::Sidecars(dir) = &mut output {
+            fs::create_dir_all(&*dir)?;
+            *dir = fs::canonicalize(&*dir)?;
+        }
+        folder.watch(output, stop)
+    }
+
+    fn watch(&self, mut output: WatchOutput, stop: &AtomicBool) -> io::Result<()> {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx).map_err(io::Error::other)?;
+        let mode = match self.recursive {
+            true => RecursiveMode::Recursive,
+            false => RecursiveMode::NonRecursive,
+        };
+        watcher.watch(&self.path, mode).map_err(io::Error::other)?;
+
+        let mut processed = match &self.ledger {
+            Some(ledger) => read_ledger(ledger)?,
+            None => BTreeSet::new(),
+        };
+        let mut pending = BTreeMap::new();
+        for file in list_files(&self.path, self.recursive) {
+            pending.insert(file, Pending::new());
+        }
+        let mut media_info = MediaInfo::new();
+
+        while !stop.load(Ordering::Relaxed) {
+            match rx.recv_timeout(self.poll_interval) {
+                Ok(Ok(event)) => {
+                    let closed = matches!(
+                        event.kind,
+                        EventKind::Access(AccessKind::Close(AccessMode::Write))
+                    );
+                    let touched =
+                        closed || matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_));
+                    for path in event.paths.into_iter().filter(|_| touched) {
+                        if path.is_dir() {
+                            for file in list_files(&path, self.recursive) {
+                                pending.entry(file).or_insert_with(Pending::new);
+                            }
+                            continue;
+                        }
+                        let state = pending.entry(path).or_insert_with(Pending::new);
+                        state.closed |= closed;
+                    }
+                }
+                Ok(Err(e)) => return Err(io::Error::other(e)),
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+
+            for path in self.complete_files(&mut pending, &output) {
+                let Some((size, modified_ms)) = file_version(&path) else {
+                    continue;
+                };
+                let key = Processed {
+                    path: path.clone(),
+                    size,
+                    modified_ms,
+                };
+                if processed.contains(&key) || self.has_sidecar(&output, &path, modified_ms) {
+                    continue;
+                }
+                let Some(record) = analyze(&mut media_info, &path) else {
+                    continue;
+                };
+
+                self.emit(&mut output, &record)?;
+                if let Some(ledger) = &self.ledger {
+                    append_ledger(ledger, &key)?;
+                }
+                processed.insert(key);
+            }
+        }
+        Ok(())
+    }
+
+    // Takes the files that are complete out of `pending`.
+    fn complete_files(
+        &self,
+        pending: &mut BTreeMap<PathBuf, Pending>,
+        output: &WatchOutput,
+    ) -> Vec<PathBuf> {
+        let now = Instant::now();
+        let mut complete = Vec::new();
+        pending.retain(|path, state| {
+            if self.ignores(path, output) {
+                return false;
+            }
+            let Some((size, modified_ms)) = file_version(path) else {
+                return false;
+            };
+            if (size, modified_ms) != (state.size, state.modified_ms) {
+                state.size = size;
+                state.modified_ms = modified_ms;
+                state.changed = now;
+                if !state.closed {
+                    return true;
+                }
+            }
+
+            let idle = now.duration_since(state.changed);
+            let done = match self.completion {
+                Completion::StableSize(settle) => idle >= settle,
+                Completion::Growing(timeout) => state.closed || idle >= timeout,
+            };
+            if done {
+                complete.push(path.clone());
+            }
+            !done
+        });
+        complete
+    }
+
+    fn ignores(&self, path: &Path, output: &WatchOutput) -> bool {
+        let hidden = path
+            .file_name()
+            .is_some_and(|x| x.to_string_lossy().starts_with('.'));
+        let sidecar = matches!(output, WatchOutput::Sidecars(dir) if path.starts_with(dir));
+        hidden || sidecar || self.ledger.as_deref() == Some(path)
+    }
+
+    fn sidecar_path(&self, dir: &Path, path: &Path) -> PathBuf {
+        let relative = path.strip_prefix(&self.path).unwrap_or(path);
+        let mut name = relative.as_os_str().to_os_string();
+        name.push(".json");
+        dir.join(name)
+    }
+
+    fn has_sidecar(&self, output: &WatchOutput, path: &Path, modified_ms: u64) -> bool {
+        let WatchOutput::Sidecars(dir) = output else {
+            return false;
+        };
+        file_version(&self.sidecar_path(dir, path))
+            .is_some_and(|(_, sidecar_ms)| sidecar_ms >= modified_ms)
+    }
+
+    fn emit(&self, output: &mut WatchOutput, record: &WatchRecord) -> io::Result<()> {
+        match output {
+            WatchOutput::Callback(callback) => callback(record),
+            WatchOutput::Sidecars(dir) => {
+                let sidecar = self.sidecar_path(dir, &record.path);
+                if let Some(parent) = sidecar.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                // Written whole then renamed, so a sidecar is never partial.
+                let partial = sidecar.with_extension("json.partial");
+                fs::write(&partial, serde_json::to_vec_pretty(record)?)?;
+                fs::rename(&partial, &sidecar)?;
+            }
+            WatchOutput::Stdout => {
+                let mut stdout = io::stdout().lock();
+                serde_json::to_writer(&mut stdout, record)?;
+                stdout.write_all(b"\n")?;
+                stdout.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Pending {
+    fn new() -> Pending {
+        Pending {
+            size: 0,
+            modified_ms: 0,
+            changed: Instant::now(),
+            closed: false,
+        }
+    }
+}
+
+// Size and modification time, `None` for anything but an existing file.
+fn file_version(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok().filter(|x| x.is_file())?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((metadata.len(), modified.as_millis() as u64))
+}
+
+fn list_files(dir: &Path, recursive: bool) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return files;
+    };
+    for path in entries.flatten().map(|x| x.path()) {
+        if path.is_dir() {
+            if recursive {
+                files.extend(list_files(&path, recursive));
+            }
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
+
+fn analyze(media_info: &mut MediaInfo, path: &Path) -> Option<WatchRecord> {
+    let (size, modified_ms) = file_version(path)?;
+    let (status, error, media) = match batch::analyze_file(media_info, path) {
+        Ok(media) => (BatchStatus::Analyzed, None, Some(media)),
+        Err((status, error)) => (status, Some(error), None),
+    };
+    Some(WatchRecord {
+        path: path.to_path_buf(),
+        size,
+        modified_ms,
+        status,
+        error,
+        media,
+    })
+}
+
+fn read_ledger(ledger: &Path) -> io::Result<BTreeSet<Processed>> {
+    let file = match fs::File::open(ledger) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(BTreeSet::new()),
+        Err(e) => return Err(e),
+    };
+    let mut processed = BTreeSet::new();
+    for line in io::BufReader::new(file).lines() {
+        // A line cut short by a crash is skipped, not fatal.
+        if let Ok(entry) = serde_json::from_str(&line?) {
+            processed.insert(entry);
+        }
+    }
+    Ok(processed)
+}
+
+fn append_ledger(ledger: &Path, entry: &Processed) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(ledger)?;
+    let mut line = serde_json::to_vec(entry)?;
+    line.push(b'\n');
+    file.write_all(&line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("mediainfo-watch-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // How long a test waits for the watch before failing. Only reached when
+    // something is broken, so it is generous for loaded machines.
+    const DEADLINE: Duration = Duration::from_secs(30);
+
+    // Runs `setup`, then writes `sentinel` into the folder and watches until
+    // its record arrives. Files are complete in the order they were written
+    // and emitted in path order, so every file written before the sentinel
+    // has been decided on by then.
+    fn watch(folder: &WatchFolder, sentinel: &str, setup: impl FnOnce()) -> Vec<WatchRecord> {
+        let (tx, rx) = mpsc::channel();
+        let output = WatchOutput::Callback(Box::new(move |record: &WatchRecord| {
+            let _ = tx.send(record.clone());
+        }));
+        let stop = AtomicBool::new(false);
+        let sentinel = folder.path.join(sentinel);
+
+        thread::scope(|scope| {
+            let watcher = scope.spawn(|| folder.run_until(output, &stop));
+            setup();
+            fs::write(&sentinel, b"last").unwrap();
+
+            let started = Instant::now();
+            let mut records = Vec::new();
+            while records
+                .last()
+                .is_none_or(|x: &WatchRecord| x.path != sentinel)
+            {
+                let left = DEADLINE.saturating_sub(started.elapsed());
+                match rx.recv_timeout(left) {
+                    Ok(record) => records.push(record),
+                    Err(_) => panic!("no record for {} after {DEADLINE:?}", sentinel.display()),
+                }
+            }
+            stop.store(true, Ordering::Relaxed);
+            watcher.join().unwrap().unwrap();
+            records
+        })
+    }
+
+    #[test]
+    fn analyzes_each_file_once_across_restarts() {
+        let dir = temp_dir("restart");
+        let ledger_dir = temp_dir("restart-ledger");
+        let ledger = ledger_dir.join("ledger.ndjson");
+        fs::write(dir.join("existing.bin"), b"landed before the watch").unwrap();
+
+        let mut folder = WatchFolder::new(&dir);
+        folder.completion = Completion::StableSize(Duration::from_millis(100));
+        folder.ledger = Some(ledger.clone());
+        folder.poll_interval = Duration::from_millis(20);
+        // Paths in records are canonical.
+        folder.path = fs::canonicalize(&dir).unwrap();
+
+        let records = watch(&folder, "zz-first.bin", || {
+            fs::write(dir.join("new.bin"), b"landed while watching").unwrap();
+            fs::write(dir.join(".partial.bin"), b"hidden").unwrap();
+        });
+        let mut names: Vec<_> = records
+            .iter()
+            .map(|x| x.path.file_name().unwrap().to_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["existing.bin", "new.bin", "zz-first.bin"]);
+        assert!(
+            records
+                .iter()
+                .all(|x| x.error.is_some() && x.media.is_none())
+        );
+
+        // Nothing changed while stopped, so only the new sentinel is
+        // analyzed.
+        let records = watch(&folder, "zz-second.bin", || {});
+        assert_eq!(records.len(), 1);
+        assert_eq!(read_ledger(&ledger).unwrap().len(), 4);
+
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(&ledger_dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_a_ledger_in_the_folder_by_default() {
+        let dir = temp_dir("default-ledger");
+        fs::write(dir.join("existing.bin"), b"landed before the watch").unwrap();
+
+        let mut folder = WatchFolder::new(&fs::canonicalize(&dir).unwrap());
+        folder.completion = Completion::StableSize(Duration::from_millis(100));
+        folder.poll_interval = Duration::from_millis(20);
+
+        let records = watch(&folder, "zz-first.bin", || {});
+        assert_eq!(records.len(), 2);
+
+        // The restart skips both files, and the ledger itself is never
+        // analyzed.
+        let records = watch(&folder, "zz-second.bin", || {});
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].path, folder.path.join("zz-second.bin"));
+        assert_eq!(
+            read_ledger(&dir.join(".mediainfo-watch.ndjson"))
+                .unwrap()
+                .len(),
+            3
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn writes_sidecars_outside_the_folder_scan() {
+        let dir = temp_dir("sidecars");
+        let sidecars = dir.join("sidecars");
+        fs::create_dir_all(dir.join("day1")).unwrap();
+        fs::write(dir.join("day1").join("clip.ts"), b"not really a stream").unwrap();
+
+        let mut folder = WatchFolder::new(&dir);
+        folder.completion = Completion::Growing(Duration::from_millis(100));
+        folder.poll_interval = Duration::from_millis(20);
+        let stop = AtomicBool::new(false);
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                folder
+                    .run_until(WatchOutput::Sidecars(sidecars.clone()), &stop)
+                    .unwrap()
+            });
+            let sidecar = sidecars.join("day1").join("clip.ts.json");
+            let started = Instant::now();
+            while !sidecar.exists() {
+                assert!(
+                    started.elapsed() < DEADLINE,
+                    "no sidecar after {DEADLINE:?}"
+                );
+                thread::sleep(Duration::from_millis(20));
+            }
+            stop.store(true, Ordering::Relaxed);
+        });
+
+        let sidecar: WatchRecord =
+            serde_json::from_slice(&fs::read(sidecars.join("day1").join("clip.ts.json")).unwrap())
+                .unwrap();
+        assert_eq!(sidecar.path, dir.join("day1").join("clip.ts"));
+        assert_eq!(sidecar.size, 19);
+        assert!(!sidecars.join("day1").join("clip.ts.json.json").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}